@@ -6,6 +6,9 @@ use clap::{Parser, Subcommand};
 use crate::terminal::{Terminal, TERM_LOCK};
 
 mod example;
+mod export;
+mod generate;
+mod import;
 mod reqwest;
 mod run;
 
@@ -74,10 +77,14 @@ impl Cli {
     }
 }
 
+#[allow(clippy::large_enum_variant)] // parsed once, never moved around
 #[derive(Debug, Subcommand)]
 enum Command {
     Run(run::Command),
     Example(example::Command),
+    Export(export::Command),
+    Generate(generate::Command),
+    Import(import::Command),
 }
 
 trait RedeCommand {
@@ -89,6 +96,9 @@ impl RedeCommand for Command {
         match self {
             Command::Run(c) => c.run(global_args).await,
             Command::Example(c) => c.run(global_args).await,
+            Command::Export(c) => c.run(global_args).await,
+            Command::Generate(c) => c.run(global_args).await,
+            Command::Import(c) => c.run(global_args).await,
         }
     }
 }
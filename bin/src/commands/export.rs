@@ -0,0 +1,416 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::{Args, ValueEnum};
+use http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, HOST};
+use http::{HeaderMap, HeaderName, Method, Version};
+use log::{info, trace};
+use rede_parser::parse_request;
+use rede_schema::auth::ApiKeyLocation;
+use rede_schema::body::{FormDataPart, FormDataValue};
+use rede_schema::tls::ClientCert;
+use rede_schema::{Auth, Body, Request, Tls};
+use url::{form_urlencoded, Url};
+
+use crate::commands::run::PlaceholderArgs;
+use crate::commands::{GlobalArgs, RedeCommand};
+use crate::errors::{ParsingError, RequestError};
+use crate::standard;
+use crate::util::{input_to_string, title_case};
+
+/// Boundary used to separate the parts of multipart bodies in the raw HTTP messages
+const BOUNDARY: &str = "rede-boundary";
+
+/// Prints the provided HTTP request in the format of other tools
+#[derive(Debug, Args)]
+#[command(
+    after_help = "Documentation: https://rede.sotoestevez.dev/reference_guide/command_line_interface/export.html"
+)]
+pub struct Command {
+    /// Request file to export
+    #[arg(default_value = "-")]
+    request: String,
+    /// Format of the exported request
+    #[arg(long, short, value_enum, default_value_t = Format::Curl)]
+    format: Format,
+    #[command(flatten)]
+    placeholders: PlaceholderArgs,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    /// curl command
+    Curl,
+    /// `HTTPie` command
+    Httpie,
+    /// Raw HTTP message
+    Http,
+}
+
+impl RedeCommand for Command {
+    async fn run(self, _: GlobalArgs) -> miette::Result<()> {
+        info!("Launched rede export with {}", self.request);
+
+        let content = input_to_string(&self.request)?;
+        trace!("Content: {content}");
+
+        let request = parse_request(&content).map_err(|e| ParsingError::parsing(content, e))?;
+        let mut request = self.placeholders.replace_placeholders(request)?;
+        add_content_type(&mut request);
+        add_cookies(&mut request);
+        add_auth(&mut request, matches!(self.format, Format::Http));
+
+        let url = Url::parse(&request.url)
+            .map_err(|e| RequestError::<Infallible>::invalid_url(&request.url, e))?;
+        let url = with_query(url, &request.query_params);
+
+        let output = match self.format {
+            Format::Curl => to_curl(&request, &url),
+            Format::Httpie => to_httpie(&request, &url),
+            Format::Http => to_http(&request, &url),
+        };
+        standard!("{output}");
+        Ok(())
+    }
+}
+
+/// Adds the Content-Type of the body when the tools can't infer it, like the client does
+fn add_content_type(request: &mut Request) {
+    let mime = match &request.body {
+        Body::Raw { mime, .. } | Body::Binary { mime, .. } => mime,
+        Body::Json(_) | Body::GraphQL(_) => &mime::APPLICATION_JSON,
+        _ => return,
+    };
+    if !request.headers.contains_key(CONTENT_TYPE) {
+        request
+            .headers
+            .insert(CONTENT_TYPE, mime.to_string().parse().unwrap());
+    }
+}
+
+/// Moves the cookies into the `Cookie` header, unless the request already has it
+fn add_cookies(request: &mut Request) {
+    let cookies = std::mem::take(&mut request.cookies);
+    if cookies.is_empty() || request.headers.contains_key(COOKIE) {
+        return;
+    }
+    let cookies = cookies
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<String>>()
+        .join("; ");
+    if let Ok(value) = cookies.parse() {
+        request.headers.insert(COOKIE, value);
+    }
+}
+
+/// Moves the authentication into the headers or query params that it generates. The basic and
+/// digest authentications are kept for the tools supporting them, and the digest one is dropped
+/// from the raw messages as it needs the challenge of the server. The `OAuth2` authentication is
+/// always dropped, as the token must be requested to the authorization server.
+fn add_auth(request: &mut Request, raw: bool) {
+    let header = match std::mem::take(&mut request.auth) {
+        Auth::Bearer { token } => Some((AUTHORIZATION, format!("Bearer {token}"))),
+        Auth::Basic { username, password } if raw => {
+            let credentials = STANDARD.encode(format!("{username}:{password}"));
+            Some((AUTHORIZATION, format!("Basic {credentials}")))
+        }
+        Auth::ApiKey {
+            key,
+            value,
+            location,
+        } => {
+            if let (ApiKeyLocation::Header, Ok(name)) = (location, HeaderName::from_str(&key)) {
+                Some((name, value))
+            } else {
+                request.query_params.push((key, value));
+                None
+            }
+        }
+        auth @ (Auth::Basic { .. } | Auth::Digest { .. }) if !raw => {
+            request.auth = auth;
+            None
+        }
+        Auth::None | Auth::Basic { .. } | Auth::Digest { .. } | Auth::OAuth2(_) => None,
+    };
+    if let Some((name, value)) = header {
+        if let Ok(value) = value.parse() {
+            request.headers.insert(name, value);
+        }
+    }
+}
+
+fn with_query(mut url: Url, query_params: &[(String, String)]) -> Url {
+    if !query_params.is_empty() {
+        url.query_pairs_mut().extend_pairs(query_params);
+    }
+    url
+}
+
+fn quote(value: &str) -> Cow<'_, str> {
+    shell_words::quote(value)
+}
+
+fn header_lines<'h>(
+    headers: &'h HeaderMap,
+    separator: &'static str,
+) -> impl Iterator<Item = String> + 'h {
+    headers.iter().map(move |(name, value)| {
+        let value = String::from_utf8_lossy(value.as_bytes());
+        format!("{}{separator}{value}", title_case(name.as_str()))
+    })
+}
+
+fn to_curl(request: &Request, url: &Url) -> String {
+    let mut command = String::from("curl");
+    match (&request.method, &request.body) {
+        (&Method::GET, Body::None) => {}
+        (&Method::HEAD, _) => command.push_str(" --head"),
+        (method, _) => write!(command, " -X {method}").unwrap(),
+    }
+    match request.http_version {
+        Version::HTTP_09 => command.push_str(" --http0.9"),
+        Version::HTTP_10 => command.push_str(" --http1.0"),
+        Version::HTTP_2 => command.push_str(" --http2"),
+        Version::HTTP_3 => command.push_str(" --http3"),
+        _ => {}
+    }
+    write!(command, " {}", quote(url.as_str())).unwrap();
+
+    let mut lines = vec![command];
+    match &request.auth {
+        Auth::Basic { username, password } => {
+            lines.push(format!("-u {}", quote(&format!("{username}:{password}"))));
+        }
+        Auth::Digest { username, password } => {
+            lines.push(format!(
+                "--digest -u {}",
+                quote(&format!("{username}:{password}"))
+            ));
+        }
+        _ => {}
+    }
+    lines.extend(curl_tls_options(&request.tls));
+    lines.extend(header_lines(&request.headers, ": ").map(|h| format!("-H {}", quote(&h))));
+    match &request.body {
+        Body::None => {}
+        Body::Raw { content, .. } => lines.push(format!("--data-raw {}", quote(content))),
+        Body::Json(value) => lines.push(format!("--data-raw {}", quote(&format!("{value:#}")))),
+        Body::GraphQL(graphql) => {
+            lines.push(format!("--data-raw {}", quote(&graphql.to_json())));
+        }
+        Body::Binary { path, .. } => {
+            lines.push(format!("--data-binary {}", quote(&format!("@{path}"))));
+        }
+        Body::FormData(form) => lines.extend(form.iter().map(|(k, v)| {
+            let options = curl_part_options(v);
+            match &v.value {
+                FormDataValue::Text(text) if options.is_empty() => {
+                    format!("--form-string {}", quote(&format!("{k}={text}")))
+                }
+                FormDataValue::Text(text) => {
+                    let text = text.replace('"', "\\\"");
+                    format!("-F {}", quote(&format!("{k}=\"{text}\"{options}")))
+                }
+                FormDataValue::File(path) => {
+                    format!("-F {}", quote(&format!("{k}=@{path}{options}")))
+                }
+            }
+        })),
+        Body::XFormUrlEncoded(form) => lines.extend(
+            form.iter()
+                .map(|(k, v)| format!("--data-urlencode {}", quote(&format!("{k}={v}")))),
+        ),
+    }
+    lines.join(" \\\n  ")
+}
+
+/// Options of curl for the TLS configuration, `--tlsv1.x` sets the minimum version
+fn curl_tls_options(tls: &Tls) -> Vec<String> {
+    let mut options = Vec::new();
+    if tls.insecure {
+        options.push(String::from("--insecure"));
+    }
+    if let Some(ca_cert) = &tls.ca_cert {
+        options.push(format!("--cacert {}", quote(ca_cert)));
+    }
+    match &tls.client_cert {
+        Some(ClientCert::Pem { cert, key }) => {
+            options.push(format!("--cert {} --key {}", quote(cert), quote(key)));
+        }
+        Some(ClientCert::Pkcs12 { path, password }) => {
+            let cert = format!("{}:{password}", path.replace(':', "\\:"));
+            options.push(format!("--cert-type P12 --cert {}", quote(&cert)));
+        }
+        None => {}
+    }
+    if let Some(version) = tls.min_version {
+        options.push(format!("--tlsv{}", version.number()));
+    }
+    options
+}
+
+/// Options of `HTTPie` for the TLS configuration, it doesn't support PKCS#12 certificates nor
+/// minimum versions
+fn httpie_tls_options(tls: &Tls) -> Vec<String> {
+    let mut options = Vec::new();
+    match (&tls.ca_cert, tls.insecure) {
+        (_, true) => options.push(String::from("--verify=no")),
+        (Some(ca_cert), false) => options.push(format!("--verify={}", quote(ca_cert))),
+        (None, false) => {}
+    }
+    if let Some(ClientCert::Pem { cert, key }) = &tls.client_cert {
+        options.push(format!("--cert={} --cert-key={}", quote(cert), quote(key)));
+    }
+    options
+}
+
+fn to_httpie(request: &Request, url: &Url) -> String {
+    let mut command = vec![String::from("http")];
+    match &request.body {
+        Body::Raw { content, .. } => command.push(format!("--raw {}", quote(content))),
+        Body::Json(value) => command.push(format!("--raw {}", quote(&format!("{value:#}")))),
+        Body::GraphQL(graphql) => command.push(format!("--raw {}", quote(&graphql.to_json()))),
+        Body::FormData(_) => command.push(String::from("--multipart")),
+        Body::XFormUrlEncoded(_) => command.push(String::from("--form")),
+        Body::None | Body::Binary { .. } => {}
+    }
+    match &request.auth {
+        Auth::Basic { username, password } => {
+            command.push(format!("-a {}", quote(&format!("{username}:{password}"))));
+        }
+        Auth::Digest { username, password } => {
+            command.push(format!(
+                "-A digest -a {}",
+                quote(&format!("{username}:{password}"))
+            ));
+        }
+        _ => {}
+    }
+    command.extend(httpie_tls_options(&request.tls));
+    command.push(request.method.to_string());
+    command.push(quote(url.as_str()).into_owned());
+    command.extend(header_lines(&request.headers, ":").map(|h| quote(&h).into_owned()));
+    match &request.body {
+        Body::FormData(form) => command.extend(form.iter().map(|(k, v)| match &v.value {
+            FormDataValue::Text(text) => quote(&format!("{k}={text}")).into_owned(),
+            FormDataValue::File(path) => match &v.content_type {
+                Some(mime) => quote(&format!("{k}@{path};type={mime}")).into_owned(),
+                None => quote(&format!("{k}@{path}")).into_owned(),
+            },
+        })),
+        Body::XFormUrlEncoded(form) => {
+            command.extend(
+                form.iter()
+                    .map(|(k, v)| quote(&format!("{k}={v}")).into_owned()),
+            );
+        }
+        Body::Binary { path, .. } => command.push(format!("< {}", quote(path))),
+        Body::None | Body::Raw { .. } | Body::Json(_) | Body::GraphQL(_) => {}
+    }
+    command.join(" ")
+}
+
+fn to_http(request: &Request, url: &Url) -> String {
+    let target = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let mut message = format!("{} {target} {:?}\r\n", request.method, request.http_version);
+
+    // the Host header goes first, as the clients do
+    let mut headers = HeaderMap::new();
+    if let Some(host) = url.host_str() {
+        let host = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        };
+        headers.insert(HOST, host.parse().unwrap());
+    }
+    headers.extend(request.headers.clone());
+
+    let body = match &request.body {
+        Body::None => None,
+        Body::Raw { content, .. } => Some(content.clone()),
+        Body::Json(value) => Some(format!("{value:#}")),
+        Body::GraphQL(graphql) => Some(graphql.to_json()),
+        Body::Binary { path, .. } => Some(format!("< {path}")),
+        Body::XFormUrlEncoded(form) => Some(
+            form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(form)
+                .finish(),
+        ),
+        Body::FormData(form) => Some(multipart(form)),
+    };
+    match &request.body {
+        Body::FormData(_) => {
+            let content_type = format!("{}; boundary={BOUNDARY}", mime::MULTIPART_FORM_DATA);
+            headers.insert(CONTENT_TYPE, content_type.parse().unwrap());
+        }
+        Body::XFormUrlEncoded(_) if !headers.contains_key(CONTENT_TYPE) => {
+            let content_type = mime::APPLICATION_WWW_FORM_URLENCODED.to_string();
+            headers.insert(CONTENT_TYPE, content_type.parse().unwrap());
+        }
+        _ => {}
+    }
+    if let (Body::Raw { .. } | Body::GraphQL(_) | Body::XFormUrlEncoded(_), Some(body)) =
+        (&request.body, &body)
+    {
+        headers.insert(CONTENT_LENGTH, body.len().into());
+    }
+
+    for header in header_lines(&headers, ": ") {
+        write!(message, "{header}\r\n").unwrap();
+    }
+    message.push_str("\r\n");
+    if let Some(body) = body {
+        message.push_str(&body);
+    }
+    message
+}
+
+/// Generates a multipart body, the content of the files is referenced with `< path`
+fn multipart(form: &BTreeMap<String, FormDataPart>) -> String {
+    let mut body = String::new();
+    for (name, part) in form {
+        write!(body, "--{BOUNDARY}\r\n").unwrap();
+        write!(body, "Content-Disposition: form-data; name=\"{name}\"").unwrap();
+        if let Some(filename) = part.file_name() {
+            write!(body, "; filename=\"{filename}\"").unwrap();
+        }
+        body.push_str("\r\n");
+        if let Some(mime) = part.mime() {
+            write!(body, "Content-Type: {mime}\r\n").unwrap();
+        }
+        for header in header_lines(&part.headers, ": ") {
+            write!(body, "{header}\r\n").unwrap();
+        }
+        match &part.value {
+            FormDataValue::Text(text) => write!(body, "\r\n{text}\r\n"),
+            FormDataValue::File(path) => write!(body, "\r\n< {path}\r\n"),
+        }
+        .unwrap();
+    }
+    write!(body, "--{BOUNDARY}--\r\n").unwrap();
+    body
+}
+
+/// Options of a curl form part with the metadata specified in the request, like `;type=text/plain`
+fn curl_part_options(part: &FormDataPart) -> String {
+    let mut options = Vec::new();
+    if let Some(content_type) = &part.content_type {
+        options.push(format!(";type={content_type}"));
+    }
+    if let Some(filename) = &part.filename {
+        options.push(format!(";filename={filename}"));
+    }
+    for header in header_lines(&part.headers, ": ") {
+        options.push(format!(";headers=\"{header}\""));
+    }
+    options.concat()
+}
@@ -0,0 +1,29 @@
+mod openapi;
+
+use clap::{Args, Subcommand};
+
+use crate::commands::{GlobalArgs, RedeCommand};
+
+/// Generates rede requests from API specifications
+#[derive(Debug, Args)]
+#[command(
+    after_help = "Documentation: https://rede.sotoestevez.dev/reference_guide/command_line_interface/generate.html"
+)]
+pub struct Command {
+    #[command(subcommand)]
+    source: Source,
+}
+
+#[derive(Debug, Subcommand)]
+enum Source {
+    #[command(name = "openapi")]
+    OpenApi(openapi::Command),
+}
+
+impl RedeCommand for Command {
+    async fn run(self, gargs: GlobalArgs) -> miette::Result<()> {
+        match self.source {
+            Source::OpenApi(c) => c.run(gargs).await,
+        }
+    }
+}
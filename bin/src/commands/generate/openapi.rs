@@ -0,0 +1,485 @@
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Args;
+use console::style;
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use log::{debug, info};
+use rede_schema::body::FormDataValue;
+use rede_schema::{Body, Request};
+#[cfg(feature = "input_params")]
+use rede_schema::{InputParam, InputType};
+use serde_json::{Map, Value};
+
+use crate::commands::import::{output, slug, unique_slug};
+use crate::commands::GlobalArgs;
+use crate::errors::{ImportError, ParsingError};
+use crate::util::request_to_toml;
+use crate::verbose;
+
+/// Methods of the operations that can be defined in a path item
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Limit of nested schemas to follow when generating the example of a body
+const MAX_DEPTH: usize = 8;
+
+/// Generates a request per operation of an `OpenAPI` 3 document
+#[derive(Debug, Args)]
+#[command(
+    after_help = "Documentation: https://rede.sotoestevez.dev/reference_guide/command_line_interface/generate.html"
+)]
+pub struct Command {
+    /// `OpenAPI` 3 document, in YAML or JSON
+    spec: String,
+    /// Directory to write the requests, by default one named like the API
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<String>,
+}
+
+impl Command {
+    pub async fn run(self, gargs: GlobalArgs) -> miette::Result<()> {
+        info!("Launched rede generate openapi with {}", self.spec);
+
+        let content = read_to_string(&self.spec).map_err(|e| ParsingError::io(&self.spec, e))?;
+        let spec: Value =
+            serde_yaml_ng::from_str(&content).map_err(|source| ImportError::InvalidOpenApi {
+                filename: self.spec.clone(),
+                source,
+            })?;
+        let version = spec
+            .get("openapi")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if !version.starts_with('3') {
+            return Err(ImportError::UnsupportedOpenApi {
+                version: version.to_string(),
+            }
+            .into());
+        }
+
+        let title = spec
+            .pointer("/info/title")
+            .and_then(Value::as_str)
+            .unwrap_or("openapi");
+        let output_dir = PathBuf::from(self.output.unwrap_or_else(|| slug(title)));
+        let base_url = server_url(&spec);
+        debug!("Generating {title} with base URL {base_url} into {output_dir:?}");
+
+        let mut names: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+        let mut files = Vec::new();
+        let paths = spec.get("paths").and_then(Value::as_object);
+        for (path, item) in paths.into_iter().flatten() {
+            let item = resolve(&spec, item);
+            for method in METHODS {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                let operation = Operation {
+                    spec: &spec,
+                    path,
+                    method,
+                    item,
+                    definition: operation,
+                };
+                let request = operation.to_request(&base_url)?;
+
+                let dir = match operation.tag() {
+                    Some(tag) => output_dir.join(slug(tag)),
+                    None => output_dir.clone(),
+                };
+                let name = unique_slug(&operation.id(), names.entry(dir.clone()).or_default());
+                let content = request_to_toml(&request).map_err(|e| {
+                    miette::miette!("There was a problem generating the request: {e}")
+                })?;
+                files.push((dir.join(format!("{name}.toml")), content));
+            }
+        }
+
+        if files.is_empty() {
+            verbose!("{} The document has no operations", style("!").yellow());
+        }
+        for (path, content) in files {
+            output(&content, Some(&path.to_string_lossy()), &gargs).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Follows the reference of the value, if it's a `$ref` object
+fn resolve<'s>(spec: &'s Value, value: &'s Value) -> &'s Value {
+    let mut value = value;
+    // the limit avoids looping over circular references
+    for _ in 0..MAX_DEPTH {
+        match value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| spec.pointer(pointer))
+        {
+            Some(referenced) => value = referenced,
+            None => break,
+        }
+    }
+    value
+}
+
+/// URL of the first server, with its variables replaced by their default values
+fn server_url(spec: &Value) -> String {
+    let Some(server) = spec.pointer("/servers/0") else {
+        return "http://localhost".to_string();
+    };
+    let mut url = server
+        .get("url")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let variables = server.get("variables").and_then(Value::as_object);
+    for (name, variable) in variables.into_iter().flatten() {
+        if let Some(default) = variable.get("default").and_then(Value::as_str) {
+            url = url.replace(&format!("{{{name}}}"), default);
+        }
+    }
+    let url = url.trim_end_matches('/');
+    if url.starts_with('/') || url.is_empty() {
+        format!("http://localhost{url}")
+    } else {
+        url.to_string()
+    }
+}
+
+struct Operation<'s> {
+    spec: &'s Value,
+    path: &'s str,
+    method: &'s str,
+    item: &'s Value,
+    definition: &'s Value,
+}
+
+impl<'s> Operation<'s> {
+    fn str(&self, key: &str) -> Option<&'s str> {
+        self.definition.get(key).and_then(Value::as_str)
+    }
+
+    fn id(&self) -> String {
+        self.str("operationId")
+            .map_or_else(|| format!("{} {}", self.method, self.path), str::to_string)
+    }
+
+    fn tag(&self) -> Option<&'s str> {
+        self.definition.pointer("/tags/0").and_then(Value::as_str)
+    }
+
+    /// Parameters of the operation, including the ones shared by all the operations of the path
+    fn parameters(&self) -> Vec<&'s Value> {
+        let shared = self.item.get("parameters").and_then(Value::as_array);
+        let own = self.definition.get("parameters").and_then(Value::as_array);
+        let mut parameters: Vec<&Value> = Vec::new();
+        for parameter in shared.into_iter().chain(own).flatten() {
+            let parameter = resolve(self.spec, parameter);
+            let key = |p: &Value| (p.get("name").cloned(), p.get("in").cloned());
+            // the parameters of the operation override the ones of the path
+            parameters.retain(|p| key(p) != key(parameter));
+            parameters.push(parameter);
+        }
+        parameters
+    }
+
+    fn to_request(&self, base_url: &str) -> Result<Request, ImportError> {
+        let method = Method::from_str(&self.method.to_uppercase()).map_err(|_| {
+            ImportError::InvalidMethod {
+                method: self.method.to_string(),
+            }
+        })?;
+        let url = format!(
+            "{{{{base_url}}}}{}",
+            self.path.replace('{', "{{").replace('}', "}}")
+        );
+
+        let mut request = Request {
+            method,
+            url,
+            variables: BTreeMap::from([("base_url".to_string(), base_url.to_string())]),
+            ..Default::default()
+        };
+
+        let name = self
+            .str("summary")
+            .map_or_else(|| self.id(), str::to_string);
+        request.metadata.insert("name".to_string(), name);
+        if let Some(description) = self.str("description") {
+            request
+                .metadata
+                .insert("description".to_string(), description.to_string());
+        }
+
+        for parameter in self.parameters() {
+            self.add_parameter(&mut request, parameter);
+        }
+
+        if let Some(body) = self.definition.get("requestBody") {
+            request.body = self.body(resolve(self.spec, body), &mut request.headers);
+        }
+
+        Ok(request)
+    }
+
+    /// Adds the parameter to its place of the request. The parameters with examples use them as
+    /// value, the rest are placeholders to input. The path params with examples are variables,
+    /// and not input params, so the user is not prompted for them. Optional parameters without
+    /// examples are omitted, with a warning.
+    fn add_parameter(&self, request: &mut Request, parameter: &Value) {
+        let Some(name) = parameter.get("name").and_then(Value::as_str) else {
+            return;
+        };
+        let location = parameter
+            .get("in")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let required = parameter
+            .get("required")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let example = self.parameter_example(parameter).map(|e| to_string(&e));
+
+        let value = match (location, example) {
+            ("path", Some(example)) => {
+                request.variables.insert(name.to_string(), example);
+                return;
+            }
+            ("path", None) => {
+                add_input_param(self.spec, request, name, parameter);
+                return;
+            }
+            ("query" | "header", Some(example)) => example,
+            ("query" | "header", None) if required => {
+                add_input_param(self.spec, request, name, parameter);
+                format!("{{{{{name}}}}}")
+            }
+            ("query" | "header", None) => {
+                verbose!(
+                    "{} Omitting the optional {location} param {name} of {} {}, it has no example",
+                    style("!").yellow(),
+                    self.method,
+                    self.path
+                );
+                return;
+            }
+            _ => return,
+        };
+
+        if location == "query" {
+            request.query_params.push((name.to_string(), value));
+        } else if let (Ok(name), Ok(value)) =
+            (HeaderName::from_str(name), HeaderValue::from_str(&value))
+        {
+            request.headers.append(name, value);
+        } else {
+            verbose!("{} Ignoring invalid header {name}", style("!").yellow());
+        }
+    }
+
+    fn parameter_example(&self, parameter: &Value) -> Option<Value> {
+        parameter
+            .get("example")
+            .cloned()
+            .or_else(|| self.first_example(parameter))
+            .or_else(|| {
+                let schema = resolve(self.spec, parameter.get("schema")?);
+                schema
+                    .get("example")
+                    .or_else(|| schema.get("default"))
+                    .cloned()
+            })
+    }
+
+    /// Value of the first of the named examples of a parameter or media type
+    fn first_example(&self, value: &Value) -> Option<Value> {
+        let (_, example) = value.get("examples")?.as_object()?.iter().next()?;
+        resolve(self.spec, example).get("value").cloned()
+    }
+
+    fn body(&self, body: &Value, headers: &mut HeaderMap) -> Body {
+        let Some(content) = body.get("content").and_then(Value::as_object) else {
+            return Body::None;
+        };
+
+        if let Some((mime, media)) = content
+            .iter()
+            .find(|(mime, _)| *mime == "application/json" || mime.ends_with("+json"))
+        {
+            let example = media
+                .get("example")
+                .cloned()
+                .or_else(|| self.first_example(media))
+                .unwrap_or_else(|| self.schema_example(media.get("schema"), 0));
+            if mime != "application/json" {
+                if let Ok(value) = HeaderValue::from_str(mime) {
+                    headers.insert(CONTENT_TYPE, value);
+                }
+            }
+            return Body::Raw {
+                content: serde_json::to_string_pretty(&example).unwrap_or_default(),
+                mime: mime::APPLICATION_JSON,
+            };
+        }
+
+        if let Some(media) = content.get("application/x-www-form-urlencoded") {
+            return Body::XFormUrlEncoded(
+                self.properties(media.get("schema"))
+                    .map(|(name, schema)| (name, to_string(&self.schema_example(Some(schema), 1))))
+                    .collect(),
+            );
+        }
+
+        if let Some(media) = content.get("multipart/form-data") {
+            return Body::FormData(
+                self.properties(media.get("schema"))
+                    .map(|(name, schema)| {
+                        let schema = resolve(self.spec, schema);
+                        let value = if schema.get("format").and_then(Value::as_str)
+                            == Some("binary")
+                        {
+                            FormDataValue::File(String::new())
+                        } else {
+                            FormDataValue::Text(to_string(&self.schema_example(Some(schema), 1)))
+                        };
+                        (name, value.into())
+                    })
+                    .collect(),
+            );
+        }
+
+        if let Some(mime) = content.keys().next() {
+            verbose!(
+                "{} Ignoring body of {} {}, {mime} is not supported",
+                style("!").yellow(),
+                self.method,
+                self.path
+            );
+        }
+        Body::None
+    }
+
+    fn properties(&self, schema: Option<&'s Value>) -> impl Iterator<Item = (String, &'s Value)> {
+        schema
+            .map(|s| resolve(self.spec, s))
+            .and_then(|s| s.get("properties"))
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(name, schema)| (name.clone(), schema))
+    }
+
+    /// Generates an example value following the schema, using its examples when possible
+    fn schema_example(&self, schema: Option<&Value>, depth: usize) -> Value {
+        let Some(schema) = schema.map(|s| resolve(self.spec, s)) else {
+            return Value::Null;
+        };
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        if let Some(example) = schema.get("example").or_else(|| schema.get("default")) {
+            return example.clone();
+        }
+        if let Some(first) = schema.pointer("/enum/0") {
+            return first.clone();
+        }
+        if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for schema in all {
+                if let Value::Object(object) = self.schema_example(Some(schema), depth + 1) {
+                    merged.extend(object);
+                }
+            }
+            return Value::Object(merged);
+        }
+        if let Some(first) = schema
+            .pointer("/oneOf/0")
+            .or_else(|| schema.pointer("/anyOf/0"))
+        {
+            return self.schema_example(Some(first), depth + 1);
+        }
+
+        let kind = match schema.get("type") {
+            // OpenAPI 3.1 allows a list of types, like ["string", "null"]
+            Some(Value::Array(types)) => types.first().and_then(Value::as_str),
+            Some(kind) => kind.as_str(),
+            None if schema.get("properties").is_some() => Some("object"),
+            None => None,
+        };
+        match kind {
+            Some("object") => Value::Object(
+                self.properties(Some(schema))
+                    .map(|(name, s)| (name, self.schema_example(Some(s), depth + 1)))
+                    .collect(),
+            ),
+            Some("array") => {
+                Value::Array(vec![self.schema_example(schema.get("items"), depth + 1)])
+            }
+            Some("string") => Value::String(String::new()),
+            Some("integer" | "number") => Value::from(0),
+            Some("boolean") => Value::Bool(false),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Defines the parameter as an input param. The patterns that are not valid regexes, as the
+/// `OpenAPI` ones follow the ECMA dialect, and the defaults that aren't one of the choices are
+/// ignored, as they would make the request invalid.
+#[cfg_attr(not(feature = "input_params"), allow(unused_variables))]
+fn add_input_param(spec: &Value, request: &mut Request, name: &str, parameter: &Value) {
+    #[cfg(feature = "input_params")]
+    {
+        let hint = parameter
+            .get("description")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let schema = parameter.get("schema").map(|s| resolve(spec, s));
+        let field = |key: &str| schema.and_then(|s| s.get(key));
+        let kind = match (
+            field("type").and_then(Value::as_str),
+            field("format").and_then(Value::as_str),
+        ) {
+            (Some("integer"), _) => InputType::Int,
+            (Some("number"), _) => InputType::Float,
+            (Some("boolean"), _) => InputType::Bool,
+            (_, Some("uri" | "url")) => InputType::Url,
+            (_, Some("email")) => InputType::Email,
+            _ => field("pattern")
+                .and_then(Value::as_str)
+                .filter(|p| regex::Regex::new(p).is_ok())
+                .map_or(InputType::String, |p| InputType::Regex(p.to_string())),
+        };
+        let choices: Vec<String> = field("enum")
+            .and_then(Value::as_array)
+            .map(|values| values.iter().map(to_string).collect())
+            .unwrap_or_default();
+        let default = field("default")
+            .map(to_string)
+            .filter(|d| choices.is_empty() || choices.contains(d));
+        request.input_params.insert(
+            name.to_string(),
+            InputParam {
+                hint,
+                kind,
+                choices,
+                default,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
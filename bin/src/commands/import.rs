@@ -0,0 +1,102 @@
+mod curl;
+mod postman;
+
+use clap::{Args, Subcommand};
+use miette::miette;
+use std::path::Path;
+use tokio::fs::{create_dir_all, File};
+use tokio::io::AsyncWriteExt;
+
+use crate::commands::{GlobalArgs, RedeCommand};
+use crate::{standard, verbose};
+
+/// Generates rede requests from other formats
+#[derive(Debug, Args)]
+#[command(
+    after_help = "Documentation: https://rede.sotoestevez.dev/reference_guide/command_line_interface/import.html"
+)]
+pub struct Command {
+    #[command(subcommand)]
+    source: Source,
+}
+
+#[derive(Debug, Subcommand)]
+enum Source {
+    Curl(curl::Command),
+    Postman(postman::Command),
+}
+
+impl RedeCommand for Command {
+    async fn run(self, gargs: GlobalArgs) -> miette::Result<()> {
+        match self.source {
+            Source::Curl(c) => c.run(gargs).await,
+            Source::Postman(c) => c.run(gargs).await,
+        }
+    }
+}
+
+/// Prints the generated content or writes it to the given file, if any
+pub(super) async fn output(
+    content: &str,
+    file: Option<&str>,
+    gargs: &GlobalArgs,
+) -> miette::Result<()> {
+    let Some(file) = file else {
+        standard!("{content}");
+        return Ok(());
+    };
+
+    verbose!("{content}");
+    if !gargs.dry_run {
+        if let Some(dir) = Path::new(file)
+            .parent()
+            .filter(|d| !d.as_os_str().is_empty())
+        {
+            create_dir_all(dir)
+                .await
+                .map_err(|e| miette!("There was a problem creating the directory {dir:?}: {e}"))?;
+        }
+        File::create(file)
+            .await
+            .map_err(|e| miette!("There was a problem creating the file {file}: {e}"))?
+            .write_all(content.as_bytes())
+            .await
+            .map_err(|e| miette!("There was a problem writing the file {file}: {e}"))?;
+    }
+    standard!("Generated {}", console::style(file).yellow());
+    Ok(())
+}
+
+/// Converts names into file names, like `Get user` or `getUser` into `get_user`
+pub(super) fn slug(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    let mut previous = None;
+    for c in name.trim().chars() {
+        let is_word_start = c.is_uppercase() && previous.is_some_and(char::is_lowercase);
+        if (is_word_start || !(c.is_alphanumeric() || c == '-')) && !slug.ends_with('_') {
+            slug.push('_');
+        }
+        if c.is_alphanumeric() || c == '-' {
+            slug.extend(c.to_lowercase());
+        }
+        previous = Some(c);
+    }
+    let slug = slug.trim_matches('_');
+    if slug.is_empty() {
+        "unnamed".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+pub(super) fn unique_slug(name: &str, used: &mut Vec<String>) -> String {
+    let base = slug(name);
+    let mut slug = base.clone();
+    let mut i = 2;
+    while used.contains(&slug) {
+        slug = format!("{base}_{i}");
+        i += 1;
+    }
+    used.push(slug.clone());
+    slug
+}
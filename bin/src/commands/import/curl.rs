@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use clap::Args;
+use console::style;
+use http::header::{ACCEPT, ACCEPT_ENCODING, CONTENT_TYPE, REFERER, USER_AGENT};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Version};
+use log::{debug, info};
+use mime::Mime;
+use rede_schema::body::{FormDataPart, FormDataValue};
+use rede_schema::tls::{ClientCert, TlsVersion};
+use rede_schema::{Auth, Body, Request, Tls};
+use serde_json::Value;
+use url::form_urlencoded;
+
+use crate::commands::GlobalArgs;
+use crate::errors::ImportError;
+use crate::util::{input_to_string, request_to_toml};
+use crate::verbose;
+
+/// Converts a curl command into a rede request
+#[derive(Debug, Args)]
+#[command(
+    after_help = "Documentation: https://rede.sotoestevez.dev/reference_guide/command_line_interface/import.html"
+)]
+pub struct Command {
+    /// Curl command to import, if omitted it will be read from the stdin
+    #[arg(default_value = "-")]
+    command: String,
+    /// File to write the generated request, by default it's printed
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<String>,
+}
+
+impl Command {
+    pub async fn run(self, gargs: GlobalArgs) -> miette::Result<()> {
+        info!("Launched rede import curl");
+
+        let command = if self.command == "-" {
+            input_to_string(&self.command)?
+        } else {
+            self.command
+        };
+        debug!("Curl command: {command}");
+
+        let request = parse_curl(&command)?;
+        let content = request_to_toml(&request)
+            .map_err(|e| miette::miette!("There was a problem generating the request: {e}"))?;
+        super::output(&content, self.output.as_deref(), &gargs).await
+    }
+}
+
+/// Piece of data of the request body, as each one of the curl data options defines it
+enum Data {
+    /// Content sent as it is
+    Text(String),
+    /// Content loaded from the file at the given path
+    File(String),
+}
+
+/// Options of the curl command relevant to build the request
+#[allow(clippy::struct_excessive_bools)] // command flags, not states
+#[derive(Default)]
+struct Curl {
+    method: Option<String>,
+    url: Option<String>,
+    version: Option<Version>,
+    headers: HeaderMap,
+    data: Vec<Data>,
+    form: BTreeMap<String, FormDataPart>,
+    cookies: BTreeMap<String, String>,
+    tls: Tls,
+    cert: Option<String>,
+    key: Option<String>,
+    cert_type: Option<String>,
+    upload: Option<String>,
+    get: bool,
+    head: bool,
+    json: bool,
+    user: Option<String>,
+    bearer: Option<String>,
+    digest: bool,
+}
+
+/// Options of curl expecting a value that are not supported by rede
+const IGNORED_WITH_VALUE: [&str; 12] = [
+    "-o",
+    "--output",
+    "-w",
+    "--write-out",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "-x",
+    "--proxy",
+    "--retry",
+    "--max-redirs",
+    "-c",
+];
+
+fn parse_curl(command: &str) -> Result<Request, ImportError> {
+    let command = command.replace("\\\r\n", " ").replace("\\\n", " ");
+    let mut args = shell_words::split(&command)
+        .map_err(|source| ImportError::CurlSyntax { source })?
+        .into_iter()
+        .peekable();
+    if args.peek().is_some_and(|a| a == "curl") {
+        args.next();
+    }
+
+    let mut curl = Curl::default();
+    while let Some(arg) = args.next() {
+        if arg.starts_with("--") {
+            curl.apply(&arg, None, &mut args)?;
+        } else if arg.len() > 1 && arg.starts_with('-') {
+            // short options can be bundled (-sL) or have their value attached (-XPOST)
+            for (i, c) in arg.char_indices().skip(1) {
+                let option = format!("-{c}");
+                let attached = Some(&arg[i + c.len_utf8()..]).filter(|v| !v.is_empty());
+                if curl.apply(&option, attached, &mut args)? && attached.is_some() {
+                    break;
+                }
+            }
+        } else if curl.url.is_none() {
+            curl.url = Some(arg);
+        } else {
+            verbose!("{} Ignoring extra URL {}", style("!").yellow(), arg);
+        }
+    }
+
+    curl.into_request()
+}
+
+impl Curl {
+    /// Applies the given option to the command. Returns true if the option consumed a value.
+    fn apply(
+        &mut self,
+        option: &str,
+        attached: Option<&str>,
+        args: &mut impl Iterator<Item = String>,
+    ) -> Result<bool, ImportError> {
+        let mut consumed = false;
+        let mut value = || {
+            consumed = true;
+            attached
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| ImportError::MissingValue {
+                    option: option.to_string(),
+                })
+        };
+
+        match option {
+            "-X" | "--request" => self.method = Some(value()?),
+            "--url" => self.url = Some(value()?),
+            "-H" | "--header" => self.header(&value()?)?,
+            "-A" | "--user-agent" => insert(&mut self.headers, USER_AGENT, &value()?)?,
+            "-e" | "--referer" => insert(&mut self.headers, REFERER, &value()?)?,
+            "-b" | "--cookie" => {
+                let cookie = value()?;
+                if cookie.contains('=') {
+                    let cookies = cookie
+                        .split(';')
+                        .filter_map(|c| c.split_once('='))
+                        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()));
+                    self.cookies.extend(cookies);
+                } else {
+                    verbose!("{} Ignoring cookie file {}", style("!").yellow(), cookie);
+                }
+            }
+            "-k" | "--insecure" => self.tls.insecure = true,
+            "--cacert" => self.tls.ca_cert = Some(value()?),
+            "-E" | "--cert" => self.cert = Some(value()?),
+            "--key" => self.key = Some(value()?),
+            "--cert-type" => self.cert_type = Some(value()?),
+            "-1" | "--tlsv1" | "--tlsv1.0" => self.tls.min_version = Some(TlsVersion::Tls1_0),
+            "--tlsv1.1" => self.tls.min_version = Some(TlsVersion::Tls1_1),
+            "--tlsv1.2" => self.tls.min_version = Some(TlsVersion::Tls1_2),
+            "--tlsv1.3" => self.tls.min_version = Some(TlsVersion::Tls1_3),
+            "-u" | "--user" => self.user = Some(value()?),
+            "--digest" => self.digest = true,
+            "--basic" => self.digest = false,
+            "--oauth2-bearer" => self.bearer = Some(value()?),
+            "-d" | "--data" | "--data-ascii" | "--data-binary" => self.data.push(data(value()?)),
+            "--data-raw" => self.data.push(Data::Text(value()?)),
+            "--data-urlencode" => self.data.push(url_encoded_data(&value()?)),
+            "--json" => {
+                self.json = true;
+                self.data.push(data(value()?));
+            }
+            "-F" | "--form" => self.form_field(&value()?, true),
+            "--form-string" => self.form_field(&value()?, false),
+            "-T" | "--upload-file" => self.upload = Some(value()?),
+            "-G" | "--get" => self.get = true,
+            "-I" | "--head" => self.head = true,
+            "--compressed" => {
+                if !self.headers.contains_key(ACCEPT_ENCODING) {
+                    insert(&mut self.headers, ACCEPT_ENCODING, "deflate, gzip")?;
+                }
+            }
+            "-0" | "--http1.0" => self.version = Some(Version::HTTP_10),
+            "--http1.1" => self.version = Some(Version::HTTP_11),
+            "--http2" | "--http2-prior-knowledge" => self.version = Some(Version::HTTP_2),
+            "--http3" => self.version = Some(Version::HTTP_3),
+            _ if IGNORED_WITH_VALUE.contains(&option) => {
+                let value = value()?;
+                verbose!(
+                    "{} Ignoring unsupported option {option} {value}",
+                    style("!").yellow()
+                );
+            }
+            _ => {
+                verbose!("{} Ignoring option {option}", style("!").yellow());
+            }
+        }
+        Ok(consumed)
+    }
+
+    fn header(&mut self, header: &str) -> Result<(), ImportError> {
+        let invalid = || ImportError::InvalidHeader {
+            header: header.to_string(),
+        };
+        // curl removes headers defined like "Name:" and sends empty ones defined like "Name;"
+        let (name, value) = if let Some((name, value)) = header.split_once(':') {
+            if value.trim().is_empty() {
+                return Ok(());
+            }
+            (name, value.trim_start())
+        } else if let Some(name) = header.strip_suffix(';') {
+            (name, "")
+        } else {
+            return Err(invalid());
+        };
+
+        let name = HeaderName::from_str(name.trim()).map_err(|_| invalid())?;
+        let value = HeaderValue::from_str(value).map_err(|_| invalid())?;
+        self.headers.append(name, value);
+        Ok(())
+    }
+
+    fn form_field(&mut self, field: &str, allow_files: bool) {
+        let Some((name, value)) = field.split_once('=') else {
+            verbose!(
+                "{} Ignoring invalid form field {field}",
+                style("!").yellow()
+            );
+            return;
+        };
+        let part = match value.strip_prefix(['@', '<']) {
+            Some(file) if allow_files => {
+                let mut modifiers = file.split(';');
+                let path = modifiers.next().unwrap_or_default();
+                let mut part = FormDataPart::from(FormDataValue::File(path.to_string()));
+                for modifier in modifiers {
+                    match modifier.split_once('=') {
+                        Some(("type", mime)) => part.content_type = mime.parse().ok(),
+                        Some(("filename", filename)) => part.filename = Some(filename.to_string()),
+                        _ => {}
+                    }
+                }
+                part
+            }
+            _ => FormDataValue::Text(value.to_string()).into(),
+        };
+        self.form.insert(name.to_string(), part);
+    }
+
+    fn into_request(mut self) -> Result<Request, ImportError> {
+        let url = self.url.take().ok_or(ImportError::MissingUrl)?;
+        let (url, mut query_params) = split_query(&url);
+
+        if self.json {
+            let json = mime::APPLICATION_JSON.to_string();
+            if !self.headers.contains_key(CONTENT_TYPE) {
+                insert(&mut self.headers, CONTENT_TYPE, &json)?;
+            }
+            if !self.headers.contains_key(ACCEPT) {
+                insert(&mut self.headers, ACCEPT, &json)?;
+            }
+        }
+
+        let has_body = !self.data.is_empty() || !self.form.is_empty() || self.upload.is_some();
+        let method = match self.method.take() {
+            Some(method) => Method::from_str(&method.to_uppercase())
+                .map_err(|_| ImportError::InvalidMethod { method })?,
+            None if self.head => Method::HEAD,
+            None if self.upload.is_some() => Method::PUT,
+            None if has_body && !self.get => Method::POST,
+            None => Method::GET,
+        };
+
+        let body = if self.get {
+            query_params.extend(form_pairs(&join_text(&self.data)));
+            Body::None
+        } else {
+            self.body()
+        };
+
+        let auth = self.auth();
+        self.tls.client_cert = self.client_cert();
+        Ok(Request {
+            method,
+            url,
+            http_version: self.version.unwrap_or_default(),
+            headers: self.headers,
+            query_params,
+            cookies: self.cookies,
+            body,
+            auth,
+            tls: self.tls,
+            ..Default::default()
+        })
+    }
+
+    /// Returns the client certificate, a PKCS#12 archive if its type is P12 or a PEM file with
+    /// its key, which can be the same file. The password follows the path after a colon.
+    fn client_cert(&mut self) -> Option<ClientCert> {
+        let cert = self.cert.take()?;
+        let (path, password) = split_cert(&cert);
+        if self
+            .cert_type
+            .as_deref()
+            .is_some_and(|t| t.eq_ignore_ascii_case("p12"))
+        {
+            Some(ClientCert::Pkcs12 { path, password })
+        } else {
+            let key = self.key.take().unwrap_or_else(|| path.clone());
+            Some(ClientCert::Pem { cert: path, key })
+        }
+    }
+
+    fn auth(&mut self) -> Auth {
+        if let Some(user) = self.user.take() {
+            let (username, password) = user.split_once(':').unwrap_or((&user, ""));
+            let (username, password) = (username.to_string(), password.to_string());
+            if self.digest {
+                Auth::Digest { username, password }
+            } else {
+                Auth::Basic { username, password }
+            }
+        } else if let Some(token) = self.bearer.take() {
+            Auth::Bearer { token }
+        } else {
+            Auth::None
+        }
+    }
+
+    fn body(&mut self) -> Body {
+        let content_type = self
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| Mime::from_str(v).ok());
+
+        if let Some(path) = self.upload.take() {
+            return Body::Binary {
+                path,
+                mime: content_type.unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            };
+        }
+        if !self.form.is_empty() {
+            return Body::FormData(std::mem::take(&mut self.form));
+        }
+        if let [Data::File(path)] = self.data.as_slice() {
+            return Body::Binary {
+                path: path.clone(),
+                mime: content_type.unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            };
+        }
+        if self.data.is_empty() {
+            return Body::None;
+        }
+
+        let content = join_text(&self.data);
+        let essence = content_type.as_ref().map(Mime::essence_str);
+        let json = serde_json::from_str::<Value>(&content)
+            .ok()
+            .filter(|v| v.is_object() || v.is_array());
+        match (essence, json) {
+            (Some(e), Some(json)) if e.contains("json") => pretty_json(&json),
+            (None, Some(json)) => pretty_json(&json),
+            (Some(e), _) if e.contains("xml") => Body::Raw {
+                content,
+                mime: mime::TEXT_XML,
+            },
+            (None | Some("application/x-www-form-urlencoded"), _) if is_form(&content) => {
+                Body::XFormUrlEncoded(form_pairs(&content).into_iter().collect())
+            }
+            (Some(e), _) if e.contains("json") => Body::Raw {
+                content,
+                mime: mime::APPLICATION_JSON,
+            },
+            _ => Body::Raw {
+                content,
+                mime: mime::TEXT_PLAIN_UTF_8,
+            },
+        }
+    }
+}
+
+/// Data options of curl read a file when the value starts with @
+fn data(value: String) -> Data {
+    match value.strip_prefix('@') {
+        Some(path) => Data::File(path.to_string()),
+        None => Data::Text(value),
+    }
+}
+
+/// Generates the data of --data-urlencode, with the format [name]=content or [name]@file
+fn url_encoded_data(value: &str) -> Data {
+    let encode = |content: &str| form_urlencoded::byte_serialize(content.as_bytes()).collect();
+    if let Some((name, content)) = value.split_once('=') {
+        if name.is_empty() {
+            Data::Text(encode(content))
+        } else {
+            Data::Text(format!("{name}={}", encode(content)))
+        }
+    } else if let Some((_, path)) = value.split_once('@') {
+        Data::File(path.to_string())
+    } else {
+        Data::Text(encode(value))
+    }
+}
+
+/// Curl joins all the data options with &, the files are not loaded so they are omitted
+fn join_text(data: &[Data]) -> String {
+    data.iter()
+        .filter_map(|d| match d {
+            Data::Text(text) => Some(text.as_str()),
+            Data::File(path) => {
+                verbose!("{} Ignoring data file {path}", style("!").yellow());
+                None
+            }
+        })
+        .collect::<Vec<&str>>()
+        .join("&")
+}
+
+fn is_form(content: &str) -> bool {
+    content
+        .split('&')
+        .all(|pair| pair.split_once('=').is_some_and(|(k, _)| !k.is_empty()))
+}
+
+fn form_pairs(content: &str) -> Vec<(String, String)> {
+    form_urlencoded::parse(content.as_bytes())
+        .into_owned()
+        .collect()
+}
+
+fn pretty_json(json: &Value) -> Body {
+    Body::Raw {
+        content: serde_json::to_string_pretty(json).unwrap_or_else(|_| json.to_string()),
+        mime: mime::APPLICATION_JSON,
+    }
+}
+
+/// Splits the URL into the address and its decoded query params, the fragment is dropped
+fn split_query(url: &str) -> (String, Vec<(String, String)>) {
+    let url = url.split('#').next().unwrap_or_default();
+    match url.split_once('?') {
+        Some((address, query)) => (address.to_string(), form_pairs(query)),
+        None => (url.to_string(), Vec::new()),
+    }
+}
+
+/// Splits the certificate of curl into its path and password, separated by the first colon not
+/// escaped with a backslash
+fn split_cert(cert: &str) -> (String, String) {
+    let mut path = String::new();
+    let mut chars = cert.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.as_str().starts_with(':') => path.push(chars.next().unwrap_or(':')),
+            ':' => return (path, chars.collect()),
+            c => path.push(c),
+        }
+    }
+    (path, String::new())
+}
+
+fn insert(headers: &mut HeaderMap, name: HeaderName, value: &str) -> Result<(), ImportError> {
+    let value = HeaderValue::from_str(value).map_err(|_| ImportError::InvalidHeader {
+        header: format!("{name}: {value}"),
+    })?;
+    headers.insert(name, value);
+    Ok(())
+}
@@ -0,0 +1,642 @@
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use clap::Args;
+use console::style;
+use http::header::CONTENT_TYPE;
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use log::{debug, info};
+use mime::Mime;
+use rede_placeholders::Placeholders;
+use rede_schema::auth::{ApiKeyLocation, ClientAuth, Grant, OAuth2};
+use rede_schema::body::{FormDataPart, FormDataValue};
+use rede_schema::{Auth as SchemaAuth, Body, Request};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{slug, unique_slug};
+use crate::commands::GlobalArgs;
+use crate::errors::{ImportError, ParsingError};
+use crate::util::request_to_toml;
+use crate::verbose;
+
+/// Converts a Postman collection into a directory of rede requests
+#[derive(Debug, Args)]
+#[command(
+    after_help = "Documentation: https://rede.sotoestevez.dev/reference_guide/command_line_interface/import.html"
+)]
+pub struct Command {
+    /// Postman collection file to import, in the v2.1 format
+    collection: String,
+    /// Postman environment file to import as a rede environment, can be repeated
+    #[arg(short, long, value_name = "FILE")]
+    environment: Vec<String>,
+    /// Directory to write the requests, by default one named like the collection
+    #[arg(short, long, value_name = "DIR")]
+    output: Option<String>,
+}
+
+impl Command {
+    pub async fn run(self, gargs: GlobalArgs) -> miette::Result<()> {
+        info!("Launched rede import postman with {}", self.collection);
+
+        let collection: Collection = read_json(&self.collection)?;
+        let output = PathBuf::from(self.output.unwrap_or_else(|| slug(&collection.info.name)));
+        debug!("Writing collection {} to {output:?}", collection.info.name);
+
+        let mut files = Vec::new();
+        let context = Context {
+            variables: collection
+                .variable
+                .iter()
+                .map(|v| (v.key.clone(), v.value()))
+                .collect(),
+            auth: collection.auth.as_ref(),
+        };
+        write_items(&collection.item, &output, &context, &mut files)?;
+
+        for environment in &self.environment {
+            let environment: Environment = read_json(environment)?;
+            let values = environment
+                .values
+                .iter()
+                .filter(|v| v.enabled.unwrap_or(true))
+                .map(|v| (v.key.clone(), v.value()))
+                .collect::<BTreeMap<String, String>>();
+            let path = output
+                .join(".rede")
+                .join("envs")
+                .join(format!("{}.toml", slug(&environment.name)));
+            files.push((path, to_toml(&values)?));
+        }
+
+        for (path, content) in files {
+            super::output(&content, Some(&path.to_string_lossy()), &gargs).await?;
+        }
+        Ok(())
+    }
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(filename: &str) -> miette::Result<T> {
+    let content = read_to_string(filename).map_err(|e| ParsingError::io(filename, e))?;
+    serde_json::from_str(&content).map_err(|source| {
+        ImportError::InvalidPostman {
+            filename: filename.to_string(),
+            source,
+        }
+        .into()
+    })
+}
+
+fn to_toml<T: Serialize>(content: &T) -> miette::Result<String> {
+    toml::to_string(content)
+        .map_err(|e| miette::miette!("There was a problem generating the request: {e}"))
+}
+
+/// Values inherited by the items of a folder
+struct Context<'c> {
+    variables: BTreeMap<String, String>,
+    auth: Option<&'c Auth>,
+}
+
+/// Generates the files of the items of a folder, the subfolders are written into directories
+/// and a suite manifest keeps the order of the requests of each folder
+fn write_items(
+    items: &[Item],
+    dir: &Path,
+    context: &Context,
+    files: &mut Vec<(PathBuf, String)>,
+) -> miette::Result<()> {
+    let mut names = Vec::new();
+    let mut requests = Vec::new();
+    for item in items {
+        let name = unique_slug(&item.name, &mut names);
+        match (&item.children, &item.request) {
+            (Some(items), _) => {
+                let context = Context {
+                    variables: context.variables.clone(),
+                    auth: item.auth.as_ref().or(context.auth),
+                };
+                write_items(items, &dir.join(name), &context, files)?;
+            }
+            (None, Some(request)) => {
+                let request = to_request(item, request, context)?;
+                let filename = format!("{name}.toml");
+                files.push((
+                    dir.join(&filename),
+                    request_to_toml(&request).map_err(|e| {
+                        miette::miette!("There was a problem generating the request: {e}")
+                    })?,
+                ));
+                requests.push(filename);
+            }
+            (None, None) => verbose!("{} Ignoring empty item {}", style("!").yellow(), item.name),
+        }
+    }
+
+    if !requests.is_empty() {
+        let manifest = BTreeMap::from([("requests", requests)]);
+        files.push((dir.join("suite.toml"), to_toml(&manifest)?));
+    }
+    Ok(())
+}
+
+fn to_request(
+    item: &Item,
+    request: &PostmanRequest,
+    context: &Context,
+) -> Result<Request, ImportError> {
+    let request = match request {
+        PostmanRequest::Url(url) => &FullRequest {
+            url: Some(PostmanUrl::Raw(url.clone())),
+            ..FullRequest::default()
+        },
+        PostmanRequest::Full(request) => request,
+    };
+
+    let method = Method::from_str(&request.method.to_uppercase()).map_err(|_| {
+        ImportError::InvalidMethod {
+            method: request.method.clone(),
+        }
+    })?;
+
+    let mut variables = BTreeMap::new();
+    let (url, query_params) = match &request.url {
+        Some(url) => url.split(&mut variables),
+        None => (String::new(), Vec::new()),
+    };
+
+    let mut headers = HeaderMap::new();
+    let mut templated_headers = Vec::new();
+    for header in request.header.iter().filter(|h| !h.disabled) {
+        if header.key.contains("{{") {
+            templated_headers.push((header.key.clone(), header.value()));
+        } else {
+            insert(&mut headers, &header.key, &header.value());
+        }
+    }
+
+    let auth = request
+        .auth
+        .as_ref()
+        .or(item.auth.as_ref())
+        .or(context.auth);
+    let auth = auth.map_or(SchemaAuth::None, Auth::to_auth);
+
+    let body = request
+        .body
+        .as_ref()
+        .filter(|b| !b.disabled)
+        .map_or(Body::None, |b| b.to_body(&headers));
+
+    let mut metadata = BTreeMap::from([("name".to_string(), item.name.clone())]);
+    if let Some(description) = request.description.as_ref().and_then(description) {
+        metadata.insert("description".to_string(), description);
+    }
+
+    let mut request = Request {
+        method,
+        url,
+        metadata,
+        headers,
+        templated_headers,
+        query_params,
+        body,
+        auth,
+        ..Default::default()
+    };
+
+    // only the collection variables used by the request are kept
+    let placeholders = Placeholders::from(&request);
+    for key in placeholders.keys() {
+        if let Some(value) = context.variables.get(key) {
+            variables.entry(key.to_string()).or_insert(value.clone());
+        }
+    }
+    request.variables = variables;
+    Ok(request)
+}
+
+fn insert(headers: &mut HeaderMap, name: &str, value: &str) {
+    if let (Ok(name), Ok(value)) = (HeaderName::from_str(name), HeaderValue::from_str(value)) {
+        headers.append(name, value);
+    } else {
+        verbose!("{} Ignoring invalid header {name}", style("!").yellow());
+    }
+}
+
+/// Descriptions can be a string or an object with the content
+fn description(description: &Value) -> Option<String> {
+    match description {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(o) => o.get("content").and_then(Value::as_str).map(str::to_string),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct Collection {
+    info: Info,
+    #[serde(default)]
+    item: Vec<Item>,
+    #[serde(default)]
+    variable: Vec<KeyValue>,
+    auth: Option<Auth>,
+}
+
+#[derive(Deserialize)]
+struct Info {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Environment {
+    name: String,
+    #[serde(default)]
+    values: Vec<KeyValue>,
+}
+
+#[derive(Deserialize)]
+struct Item {
+    name: String,
+    #[serde(rename = "item")]
+    children: Option<Vec<Item>>,
+    request: Option<PostmanRequest>,
+    auth: Option<Auth>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PostmanRequest {
+    Url(String),
+    Full(Box<FullRequest>),
+}
+
+#[derive(Default, Deserialize)]
+struct FullRequest {
+    #[serde(default = "default_method")]
+    method: String,
+    #[serde(default)]
+    header: Vec<KeyValue>,
+    url: Option<PostmanUrl>,
+    body: Option<PostmanBody>,
+    auth: Option<Auth>,
+    description: Option<Value>,
+}
+
+fn default_method() -> String {
+    Method::GET.to_string()
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PostmanUrl {
+    Raw(String),
+    Full {
+        raw: Option<String>,
+        protocol: Option<String>,
+        host: Option<Value>,
+        path: Option<Value>,
+        #[serde(default)]
+        query: Vec<KeyValue>,
+        #[serde(default)]
+        variable: Vec<KeyValue>,
+    },
+}
+
+impl PostmanUrl {
+    /// Splits the URL into the address and the query params. The path variables, like `:id`,
+    /// are converted into placeholders and their values added to the variables.
+    fn split(&self, variables: &mut BTreeMap<String, String>) -> (String, Vec<(String, String)>) {
+        let (raw, query, path_variables) = match self {
+            PostmanUrl::Raw(raw) => (raw.clone(), None, [].as_slice()),
+            PostmanUrl::Full {
+                raw,
+                protocol,
+                host,
+                path,
+                query,
+                variable,
+            } => {
+                let raw = raw.clone().unwrap_or_else(|| {
+                    let host = join(host.as_ref(), ".");
+                    let path = join(path.as_ref(), "/");
+                    let protocol = protocol
+                        .as_deref()
+                        .map_or(String::new(), |p| format!("{p}://"));
+                    format!("{protocol}{host}/{path}")
+                });
+                let query = query
+                    .iter()
+                    .filter(|q| !q.disabled)
+                    .map(|q| (q.key.clone(), q.value()))
+                    .collect();
+                (raw, Some(query), variable.as_slice())
+            }
+        };
+
+        let (address, raw_query) = match raw.split_once('?') {
+            Some((address, query)) => (address.to_string(), Some(query)),
+            None => (raw.clone(), None),
+        };
+        let query = query.unwrap_or_else(|| {
+            raw_query
+                .map(|q| {
+                    url::form_urlencoded::parse(q.as_bytes())
+                        .into_owned()
+                        .collect()
+                })
+                .unwrap_or_default()
+        });
+
+        let mut address = address;
+        for variable in path_variables {
+            let segment = format!(":{}", variable.key);
+            if address.split('/').any(|s| s == segment) {
+                address = address
+                    .split('/')
+                    .map(|s| {
+                        if s == segment {
+                            format!("{{{{{}}}}}", variable.key)
+                        } else {
+                            s.to_string()
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join("/");
+                variables.insert(variable.key.clone(), variable.value());
+            }
+        }
+        (address, query)
+    }
+}
+
+/// Hosts and paths can be strings or arrays of segments
+fn join(value: Option<&Value>, separator: &str) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(segments)) => segments
+            .iter()
+            .map(|s| match s {
+                Value::String(s) => s.clone(),
+                Value::Object(o) => o
+                    .get("value")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                _ => s.to_string(),
+            })
+            .collect::<Vec<String>>()
+            .join(separator),
+        _ => String::new(),
+    }
+}
+
+#[derive(Deserialize)]
+struct PostmanBody {
+    mode: String,
+    raw: Option<String>,
+    #[serde(default)]
+    urlencoded: Vec<KeyValue>,
+    #[serde(default)]
+    formdata: Vec<KeyValue>,
+    file: Option<PostmanFile>,
+    graphql: Option<GraphQl>,
+    options: Option<Value>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Deserialize)]
+struct PostmanFile {
+    src: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GraphQl {
+    query: String,
+    variables: Option<String>,
+}
+
+impl PostmanBody {
+    fn to_body(&self, headers: &HeaderMap) -> Body {
+        let content_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| Mime::from_str(v).ok());
+
+        match self.mode.as_str() {
+            "raw" => {
+                let language = self
+                    .options
+                    .as_ref()
+                    .and_then(|o| o.pointer("/raw/language"))
+                    .and_then(Value::as_str);
+                let mime = content_type.unwrap_or(match language {
+                    Some("json") => mime::APPLICATION_JSON,
+                    Some("xml") => mime::TEXT_XML,
+                    Some("html") => mime::TEXT_HTML,
+                    Some("javascript") => mime::APPLICATION_JAVASCRIPT,
+                    _ => mime::TEXT_PLAIN_UTF_8,
+                });
+                Body::Raw {
+                    content: self.raw.clone().unwrap_or_default(),
+                    mime,
+                }
+            }
+            "urlencoded" => Body::XFormUrlEncoded(
+                self.urlencoded
+                    .iter()
+                    .filter(|v| !v.disabled)
+                    .map(|v| (v.key.clone(), v.value()))
+                    .collect(),
+            ),
+            "formdata" => Body::FormData(
+                self.formdata
+                    .iter()
+                    .filter(|v| !v.disabled)
+                    .map(|v| {
+                        let value = if v.kind.as_deref() == Some("file") {
+                            FormDataValue::File(v.src())
+                        } else {
+                            FormDataValue::Text(v.value())
+                        };
+                        let part = FormDataPart {
+                            value,
+                            content_type: v.content_type.as_deref().and_then(|m| m.parse().ok()),
+                            ..Default::default()
+                        };
+                        (v.key.clone(), part)
+                    })
+                    .collect(),
+            ),
+            "file" => Body::Binary {
+                path: self
+                    .file
+                    .as_ref()
+                    .and_then(|f| f.src.clone())
+                    .unwrap_or_default(),
+                mime: content_type.unwrap_or(mime::APPLICATION_OCTET_STREAM),
+            },
+            "graphql" => {
+                let Some(graphql) = &self.graphql else {
+                    return Body::None;
+                };
+                let variables = graphql
+                    .variables
+                    .as_deref()
+                    .and_then(|v| serde_json::from_str::<Value>(v).ok())
+                    .unwrap_or(Value::Null);
+                let content = serde_json::json!({
+                    "query": graphql.query,
+                    "variables": variables,
+                });
+                Body::Raw {
+                    content: serde_json::to_string_pretty(&content).unwrap_or_default(),
+                    mime: mime::APPLICATION_JSON,
+                }
+            }
+            mode => {
+                verbose!("{} Ignoring body of mode {mode}", style("!").yellow());
+                Body::None
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Auth {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    basic: Vec<KeyValue>,
+    #[serde(default)]
+    bearer: Vec<KeyValue>,
+    #[serde(default)]
+    digest: Vec<KeyValue>,
+    #[serde(default)]
+    apikey: Vec<KeyValue>,
+    #[serde(default)]
+    oauth2: Vec<KeyValue>,
+}
+
+impl Auth {
+    /// Translates the authentication into the rede one
+    fn to_auth(&self) -> SchemaAuth {
+        let get = |values: &[KeyValue], key: &str| {
+            values
+                .iter()
+                .find(|v| v.key == key)
+                .map(KeyValue::value)
+                .unwrap_or_default()
+        };
+
+        match self.kind.as_str() {
+            "bearer" => SchemaAuth::Bearer {
+                token: get(&self.bearer, "token"),
+            },
+            "basic" => SchemaAuth::Basic {
+                username: get(&self.basic, "username"),
+                password: get(&self.basic, "password"),
+            },
+            "digest" => SchemaAuth::Digest {
+                username: get(&self.digest, "username"),
+                password: get(&self.digest, "password"),
+            },
+            "apikey" => SchemaAuth::ApiKey {
+                key: get(&self.apikey, "key"),
+                value: get(&self.apikey, "value"),
+                location: if get(&self.apikey, "in") == "query" {
+                    ApiKeyLocation::Query
+                } else {
+                    ApiKeyLocation::Header
+                },
+            },
+            "oauth2" => self.to_oauth2(),
+            "noauth" => SchemaAuth::None,
+            kind => {
+                verbose!("{} Ignoring auth of type {kind}", style("!").yellow());
+                SchemaAuth::None
+            }
+        }
+    }
+
+    /// Translates the `OAuth2` authentication, only the grants that don't need the interaction of
+    /// the user with the browser are supported
+    fn to_oauth2(&self) -> SchemaAuth {
+        let get = |key: &str| {
+            self.oauth2
+                .iter()
+                .find(|v| v.key == key)
+                .map(KeyValue::value)
+                .unwrap_or_default()
+        };
+
+        let grant = match get("grant_type").as_str() {
+            "client_credentials" => Grant::ClientCredentials,
+            "password_credentials" => Grant::Password {
+                username: get("username"),
+                password: get("password"),
+            },
+            grant => {
+                verbose!(
+                    "{} Ignoring OAuth2 auth with grant {grant}",
+                    style("!").yellow()
+                );
+                return SchemaAuth::None;
+            }
+        };
+        SchemaAuth::OAuth2(OAuth2 {
+            token_url: get("accessTokenUrl"),
+            client_id: get("clientId"),
+            client_secret: get("clientSecret"),
+            scope: get("scope"),
+            client_auth: match get("client_authentication").as_str() {
+                "header" => ClientAuth::Basic,
+                _ => ClientAuth::Post,
+            },
+            grant,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct KeyValue {
+    #[serde(default)]
+    key: String,
+    #[serde(default)]
+    value: Value,
+    #[serde(default)]
+    disabled: bool,
+    enabled: Option<bool>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    src: Option<Value>,
+    #[serde(rename = "contentType")]
+    content_type: Option<String>,
+}
+
+impl KeyValue {
+    fn value(&self) -> String {
+        match &self.value {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            value => value.to_string(),
+        }
+    }
+
+    /// Files of the form data can have one or multiple sources, only the first one is used
+    fn src(&self) -> String {
+        match &self.src {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Array(a)) => a
+                .first()
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            _ => String::new(),
+        }
+    }
+}
@@ -1,19 +1,36 @@
 use crate::commands::run::ClientProperties;
-use http::header::CONTENT_TYPE;
-use http::HeaderMap;
+use console::style;
+use digest_auth::{AuthContext, HttpMethod};
+use http::header::{
+    ACCEPT_ENCODING, AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE, COOKIE, WWW_AUTHENTICATE,
+};
+use http::{HeaderMap, HeaderValue, StatusCode};
 use log::debug;
 use mime::Mime;
-use rede_schema::body::FormDataValue;
-use rede_schema::{Body, Request};
+use rede_schema::auth::ApiKeyLocation;
+use rede_schema::body::{Compression, FormDataValue};
+use rede_schema::{Auth, Body, Request};
 use reqwest::redirect::Policy;
 use reqwest::{multipart, ClientBuilder, Request as Reqwest, RequestBuilder, Response, Url};
+use std::sync::Arc;
 use tokio::fs::File;
 use tokio_util::codec::{BytesCodec, FramedRead};
+use url::form_urlencoded;
 
 use crate::errors::RequestError;
+use crate::util::compress;
+use crate::verbose;
+use cookies::CookieJar;
+
+mod cookies;
+mod oauth2;
+mod tls;
 
 type Error = RequestError<reqwest::Error>;
 
+/// Encodings accepted in the responses when the decompression is enabled
+const ACCEPTED_ENCODINGS: &str = "gzip, deflate, br, zstd";
+
 pub struct Client {
     properties: ClientProperties,
 }
@@ -24,49 +41,20 @@ impl Client {
     }
 
     pub async fn send(self, req: Request) -> Result<Response, Error> {
-        let url = Url::parse(&req.url).map_err(|e| RequestError::invalid_url(&req.url, e))?;
-
-        let client = self.build_client()?;
-        let reqwest = Reqwest::new(req.method, url);
-
-        let builder = RequestBuilder::from_parts(client, reqwest)
-            .version(req.http_version)
-            .query(&req.query_params);
-
-        let mut headers = req.headers;
-
-        let builder = match req.body {
-            Body::Raw { mime, content } => {
-                set_content_type(&mut headers, &mime);
-                builder.body(content)
-            }
-            Body::Binary { mime, path } => {
-                set_content_type(&mut headers, &mime);
-                let body = file_to_body(&path).await?;
-                builder.body(body)
-            }
-            Body::FormData(map) => {
-                let mut form = multipart::Form::new();
-                for (k, v) in map {
-                    form = match v {
-                        FormDataValue::Text(content) => form.text(k, content),
-                        FormDataValue::File(path) => {
-                            let body = file_to_body(&path).await?;
-                            form.part(k, multipart::Part::stream(body))
-                        }
-                    }
-                }
-                builder.multipart(form)
-            }
-            Body::XFormUrlEncoded(form) => builder.form(&form),
-            Body::None => builder,
+        let jar = match &self.properties.cookie_jar {
+            Some(path) => Some(Arc::new(CookieJar::load(path)?)),
+            None => None,
+        };
+        let client = self.build_client(jar.clone())?;
+        let decompress = self.properties.decompress;
+        let response = exchange(&client, &req, jar.as_deref(), decompress).await?;
+        if let Some(jar) = jar {
+            jar.save()?;
         }
-        .headers(headers);
-
-        Ok(builder.send().await?)
+        Ok(response)
     }
 
-    fn build_client(&self) -> Result<reqwest::Client, reqwest::Error> {
+    fn build_client(&self, jar: Option<Arc<CookieJar>>) -> Result<reqwest::Client, Error> {
         let mut client = ClientBuilder::new();
         if let Some(timeout) = self.properties.timeout {
             client = client.timeout(timeout);
@@ -76,8 +64,279 @@ impl Client {
             (false, Some(val)) => client.redirect(Policy::limited(val)),
             _ => client,
         };
-        client.build()
+        if let Some(jar) = jar {
+            client = client.cookie_provider(jar);
+        }
+        client = tls::configure(client, &self.properties.tls)?;
+        Ok(client.build()?)
+    }
+}
+
+/// Sends the request, sending it again if the server answers with a digest challenge. The
+/// cookies of the request are sent along with the ones of the jar for its URL.
+async fn exchange(
+    client: &reqwest::Client,
+    req: &Request,
+    jar: Option<&CookieJar>,
+    decompress: bool,
+) -> Result<Response, Error> {
+    let compressed = match req.compression {
+        Some(compression) => compress_body(req, compression).await?,
+        None => None,
+    };
+    let compressed = compressed.as_deref();
+    let cookies = cookie_header(req, jar);
+    let authorization = match &req.auth {
+        Auth::OAuth2(auth) => Some(format!(
+            "Bearer {}",
+            oauth2::access_token(client, auth).await?
+        )),
+        _ => None,
+    };
+    let parts = BuildParts {
+        compressed,
+        cookies: cookies.as_deref(),
+        decompress,
+    };
+    // the digest answer must use the URI of the sent request, not the one of the response,
+    // which differs after a redirection
+    let request = build(client, req, authorization, &parts).await?.build()?;
+    let sent_url = request.url().clone();
+    let response = client.execute(request).await?;
+
+    if let Auth::OAuth2(auth) = &req.auth {
+        if response.status() == StatusCode::UNAUTHORIZED {
+            debug!("OAuth2 token rejected, removing it from the cache");
+            oauth2::forget(auth);
+        }
+    }
+
+    if let Auth::Digest { username, password } = &req.auth {
+        if let Some(challenge) = digest_challenge(&response) {
+            debug!("answering digest challenge: {challenge}");
+            let authorization = answer_digest(req, &sent_url, username, password, &challenge)?;
+            return Ok(build(client, req, Some(authorization), &parts)
+                .await?
+                .send()
+                .await?);
+        }
     }
+
+    Ok(response)
+}
+
+/// Parts of the request prepared before building it, to reuse them if it's sent twice
+struct BuildParts<'p> {
+    /// Compressed body, if the request must be compressed
+    compressed: Option<&'p [u8]>,
+    /// Value of the `Cookie` header, if the request has its own cookies
+    cookies: Option<&'p str>,
+    /// Accepts compressed responses, as they will be decompressed. It's only set in this
+    /// request, as the client can't decompress the responses of the rest, like the `OAuth2` ones
+    decompress: bool,
+}
+
+/// Returns the `Cookie` header with the cookies of the request and the ones of the jar for its
+/// URL, the cookies of the request take precedence. Without cookies in the request, the client
+/// adds the ones of the jar by itself.
+fn cookie_header(req: &Request, jar: Option<&CookieJar>) -> Option<String> {
+    if req.cookies.is_empty() {
+        return None;
+    }
+    let mut cookies = match (jar, Url::parse(&req.url)) {
+        (Some(jar), Ok(url)) => jar.values(&url),
+        _ => Vec::new(),
+    };
+    cookies.retain(|(name, _)| !req.cookies.contains_key(name));
+    cookies.extend(req.cookies.iter().map(|(k, v)| (k.clone(), v.clone())));
+    let cookies = cookies
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<String>>();
+    Some(cookies.join("; "))
+}
+
+/// Builds the HTTP request. As the digest authentication needs to send the request twice, the
+/// request is borrowed, and the files of the body are loaded with each build. The authorization
+/// of the digest and `OAuth2` authentications is obtained beforehand and given to the build,
+/// along with the rest of prepared parts.
+async fn build(
+    client: &reqwest::Client,
+    req: &Request,
+    authorization: Option<String>,
+    parts: &BuildParts<'_>,
+) -> Result<RequestBuilder, Error> {
+    let url = Url::parse(&req.url).map_err(|e| RequestError::invalid_url(&req.url, e))?;
+    let reqwest = Reqwest::new(req.method.clone(), url);
+
+    let builder = RequestBuilder::from_parts(client.clone(), reqwest)
+        .version(req.http_version)
+        .query(&req.query_params);
+
+    let mut headers = req.headers.clone();
+    if parts.decompress && !headers.contains_key(ACCEPT_ENCODING) {
+        headers.insert(
+            ACCEPT_ENCODING,
+            HeaderValue::from_static(ACCEPTED_ENCODINGS),
+        );
+    }
+    if let Some(cookies) = parts.cookies.and_then(|c| HeaderValue::from_str(c).ok()) {
+        headers.insert(COOKIE, cookies);
+    }
+
+    let builder = match (&req.body, parts.compressed.zip(req.compression)) {
+        (body, Some((compressed, compression))) => {
+            if let Some(mime) = body.mime() {
+                set_content_type(&mut headers, mime);
+            }
+            let encoding = HeaderValue::from_static(compression.encoding());
+            headers.insert(CONTENT_ENCODING, encoding);
+            builder.body(compressed.to_vec())
+        }
+        (Body::Raw { mime, content }, None) => {
+            set_content_type(&mut headers, mime);
+            builder.body(content.clone())
+        }
+        (Body::Json(value), None) => {
+            set_content_type(&mut headers, &mime::APPLICATION_JSON);
+            builder.body(format!("{value:#}"))
+        }
+        (Body::Binary { mime, path }, None) => {
+            set_content_type(&mut headers, mime);
+            let body = file_to_body(path).await?;
+            builder.body(body)
+        }
+        (Body::FormData(map), None) => {
+            let mut form = multipart::Form::new();
+            for (k, v) in map {
+                let mut part = match &v.value {
+                    FormDataValue::Text(content) => multipart::Part::text(content.clone()),
+                    FormDataValue::File(path) => multipart::Part::stream(file_to_body(path).await?),
+                };
+                if let Some(filename) = v.file_name() {
+                    part = part.file_name(filename.into_owned());
+                }
+                if let Some(mime) = v.mime() {
+                    part = part.mime_str(mime.as_ref())?;
+                }
+                form = form.part(k.clone(), part.headers(v.headers.clone()));
+            }
+            builder.multipart(form)
+        }
+        (Body::XFormUrlEncoded(form), None) => builder.form(form),
+        (Body::GraphQL(graphql), None) => {
+            set_content_type(&mut headers, &mime::APPLICATION_JSON);
+            builder.body(graphql.to_json())
+        }
+        (Body::None, None) => builder,
+    }
+    .headers(headers);
+
+    Ok(match &req.auth {
+        Auth::None => builder,
+        Auth::Basic { username, password } => {
+            builder.basic_auth(username, Some(password).filter(|p| !p.is_empty()))
+        }
+        Auth::Bearer { token } => builder.bearer_auth(token),
+        Auth::Digest { .. } | Auth::OAuth2(_) => match authorization {
+            Some(authorization) => builder.header(AUTHORIZATION, authorization),
+            None => builder,
+        },
+        Auth::ApiKey {
+            key,
+            value,
+            location: ApiKeyLocation::Header,
+        } => builder.header(key, value),
+        Auth::ApiKey {
+            key,
+            value,
+            location: ApiKeyLocation::Query,
+        } => builder.query(&[(key, value)]),
+    })
+}
+
+/// Loads the body of the request and compresses it, printing the size before and after the
+/// compression. Multipart bodies are streamed by the client, so they are sent uncompressed.
+async fn compress_body(req: &Request, compression: Compression) -> Result<Option<Vec<u8>>, Error> {
+    let bytes = match &req.body {
+        Body::None => return Ok(None),
+        Body::FormData(_) => {
+            verbose!(
+                "{} Multipart bodies can't be compressed, sending it uncompressed\n",
+                style("!").yellow()
+            );
+            return Ok(None);
+        }
+        Body::Raw { content, .. } => content.as_bytes().to_vec(),
+        Body::Json(value) => format!("{value:#}").into_bytes(),
+        Body::GraphQL(graphql) => graphql.to_json().into_bytes(),
+        Body::XFormUrlEncoded(form) => form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish()
+            .into_bytes(),
+        Body::Binary { path, .. } => tokio::fs::read(path)
+            .await
+            .map_err(|e| RequestError::io(path, e))?,
+    };
+    let compressed = compress(&bytes, compression).map_err(|source| RequestError::Compression {
+        compression: compression.to_string(),
+        source,
+    })?;
+    verbose!(
+        "{} Body compressed with {compression}: {} -> {} bytes\n",
+        style("~").cyan(),
+        bytes.len(),
+        compressed.len()
+    );
+    Ok(Some(compressed))
+}
+
+/// Returns the digest challenge of the response, if it's asking for authentication
+fn digest_challenge(response: &Response) -> Option<String> {
+    if response.status() != StatusCode::UNAUTHORIZED {
+        return None;
+    }
+    response
+        .headers()
+        .get_all(WWW_AUTHENTICATE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .find(|v| {
+            v.get(..6)
+                .is_some_and(|scheme| scheme.eq_ignore_ascii_case("digest"))
+        })
+        .map(str::to_string)
+}
+
+/// Answers the digest challenge for the request sent to the given URL
+fn answer_digest(
+    req: &Request,
+    url: &Url,
+    username: &str,
+    password: &str,
+    challenge: &str,
+) -> Result<String, Error> {
+    let uri = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    let body = match &req.body {
+        Body::Raw { content, .. } => Some(content.as_bytes()),
+        _ => None,
+    };
+    let context = AuthContext::new_with_method(
+        username,
+        password,
+        uri,
+        body,
+        HttpMethod::from(req.method.as_str()),
+    );
+    let mut prompt =
+        digest_auth::parse(challenge).map_err(|source| RequestError::Digest { source })?;
+    let answer = prompt
+        .respond(&context)
+        .map_err(|source| RequestError::Digest { source })?;
+    Ok(answer.to_header_string())
 }
 
 fn set_content_type(headers: &mut HeaderMap, mime: &Mime) {
@@ -0,0 +1,263 @@
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use cookie_store::{CookieDomain, CookieExpiration, CookieStore, RawCookie};
+use http::HeaderValue;
+use log::debug;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::Error;
+use crate::errors::RequestError;
+
+/// First line of the Netscape cookie files, the format used by curl and the browsers
+const NETSCAPE_HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// Prefix of the lines of the Netscape cookie files with HTTP only cookies
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// Cookie as stored in the cookie jar files
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    /// If the cookie is also sent to the subdomains of the domain
+    #[serde(default)]
+    include_subdomains: bool,
+    #[serde(default = "root_path")]
+    path: String,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+    /// Unix timestamp, in seconds, of the expiration of the cookie, session cookies don't have it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<i64>,
+}
+
+fn root_path() -> String {
+    String::from("/")
+}
+
+/// Format of the cookie jar file, JSON files are those with the `.json` extension
+#[derive(Clone, Copy)]
+enum Format {
+    Netscape,
+    Json,
+}
+
+impl Format {
+    fn of(path: &str) -> Self {
+        match Path::new(path).extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Format::Json,
+            _ => Format::Netscape,
+        }
+    }
+}
+
+/// Cookie store of the client. It's loaded from the cookie jar file, and saved back to it with
+/// the cookies set by the responses, so the cookies are kept across runs.
+pub struct CookieJar {
+    path: String,
+    store: Mutex<CookieStore>,
+}
+
+impl CookieJar {
+    /// Loads the cookies of the file, the jar starts empty if the file doesn't exist yet.
+    /// The expired cookies are discarded.
+    pub fn load(path: &str) -> Result<Self, Error> {
+        let cookies = if Path::new(path).exists() {
+            let content = read_to_string(path).map_err(|e| RequestError::io(path, e))?;
+            match Format::of(path) {
+                Format::Json => serde_json::from_str(&content)
+                    .map_err(|e| RequestError::cookie_jar(path, e.to_string()))?,
+                Format::Netscape => from_netscape(&content),
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut store = CookieStore::default();
+        for cookie in cookies {
+            cookie.insert_into(&mut store);
+        }
+        debug!("loaded {} cookies from {path}", store.iter_any().count());
+        Ok(Self {
+            path: path.to_string(),
+            store: Mutex::new(store),
+        })
+    }
+
+    /// Saves the unexpired cookies of the jar in its file, session cookies included
+    pub fn save(&self) -> Result<(), Error> {
+        let cookies: Vec<StoredCookie> = self
+            .store
+            .lock()
+            .unwrap()
+            .iter_unexpired()
+            .filter_map(StoredCookie::from_cookie)
+            .collect();
+        let content = match Format::of(&self.path) {
+            Format::Json => serde_json::to_string_pretty(&cookies)
+                .expect("the stored cookies are always serializable"),
+            Format::Netscape => to_netscape(&cookies),
+        };
+        if let Some(dir) = Path::new(&self.path).parent() {
+            create_dir_all(dir).map_err(|e| RequestError::io(&self.path, e))?;
+        }
+        debug!("saving {} cookies in {}", cookies.len(), self.path);
+        write(&self.path, content).map_err(|e| RequestError::io(&self.path, e))
+    }
+
+    /// Returns the names and values of the cookies of the jar to send to the URL
+    pub fn values(&self, url: &Url) -> Vec<(String, String)> {
+        self.store
+            .lock()
+            .unwrap()
+            .get_request_values(url)
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies = cookie_headers
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(|header| RawCookie::parse(header.to_string()).ok());
+        self.store
+            .lock()
+            .unwrap()
+            .store_response_cookies(cookies, url);
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let cookies = self
+            .values(url)
+            .into_iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<String>>()
+            .join("; ");
+        if cookies.is_empty() {
+            None
+        } else {
+            HeaderValue::from_str(&cookies).ok()
+        }
+    }
+}
+
+impl StoredCookie {
+    /// Converts the cookie of the store, ignoring those without a valid domain
+    fn from_cookie(cookie: &cookie_store::Cookie) -> Option<Self> {
+        let (domain, include_subdomains) = match &cookie.domain {
+            CookieDomain::HostOnly(domain) => (domain.clone(), false),
+            CookieDomain::Suffix(domain) => (domain.clone(), true),
+            CookieDomain::NotPresent | CookieDomain::Empty => return None,
+        };
+        Some(Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain,
+            include_subdomains,
+            path: cookie.path.as_ref().to_string(),
+            secure: cookie.secure().unwrap_or_default(),
+            http_only: cookie.http_only().unwrap_or_default(),
+            expires: match cookie.expires {
+                CookieExpiration::AtUtc(expires) => Some(expires.unix_timestamp()),
+                CookieExpiration::SessionEnd => None,
+            },
+        })
+    }
+
+    /// Inserts the cookie into the store as if it was set by a response of its domain
+    fn insert_into(self, store: &mut CookieStore) {
+        let scheme = if self.secure { "https" } else { "http" };
+        let Ok(url) = Url::parse(&format!("{scheme}://{}{}", self.domain, self.path)) else {
+            debug!(
+                "ignoring cookie {} with invalid domain {}",
+                self.name, self.domain
+            );
+            return;
+        };
+        let mut cookie = RawCookie::build(self.name, self.value)
+            .path(self.path)
+            .secure(self.secure)
+            .http_only(self.http_only);
+        if self.include_subdomains {
+            cookie = cookie.domain(self.domain);
+        }
+        if let Some(expires) = self
+            .expires
+            .and_then(|e| OffsetDateTime::from_unix_timestamp(e).ok())
+        {
+            cookie = cookie.expires(expires);
+        }
+        if let Err(e) = store.insert_raw(&cookie.finish(), &url) {
+            debug!("ignoring cookie of the jar: {e}");
+        }
+    }
+}
+
+/// Parses the cookies of a Netscape cookie file, ignoring the comments and invalid lines
+fn from_netscape(content: &str) -> Vec<StoredCookie> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let [domain, include_subdomains, path, secure, expires, name, value] =
+                line.split('\t').collect::<Vec<&str>>()[..]
+            else {
+                debug!("ignoring invalid line of the cookie jar: {line}");
+                return None;
+            };
+            Some(StoredCookie {
+                name: name.to_string(),
+                value: value.to_string(),
+                domain: domain.trim_start_matches('.').to_string(),
+                include_subdomains: include_subdomains.eq_ignore_ascii_case("TRUE"),
+                path: path.to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                http_only,
+                expires: expires.parse().ok().filter(|e| *e > 0),
+            })
+        })
+        .collect()
+}
+
+/// Writes the cookies in the Netscape format, session cookies have 0 as expiration
+fn to_netscape(cookies: &[StoredCookie]) -> String {
+    let flag = |value: bool| if value { "TRUE" } else { "FALSE" };
+    let mut lines = vec![NETSCAPE_HEADER.to_string(), String::new()];
+    for cookie in cookies {
+        let prefix = if cookie.http_only {
+            HTTP_ONLY_PREFIX
+        } else {
+            ""
+        };
+        let domain = if cookie.include_subdomains {
+            format!(".{}", cookie.domain)
+        } else {
+            cookie.domain.clone()
+        };
+        lines.push(format!(
+            "{prefix}{domain}\t{}\t{}\t{}\t{}\t{}\t{}",
+            flag(cookie.include_subdomains),
+            cookie.path,
+            flag(cookie.secure),
+            cookie.expires.unwrap_or_default(),
+            cookie.name,
+            cookie.value
+        ));
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
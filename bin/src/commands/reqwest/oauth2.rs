@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, read_to_string, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::header::ACCEPT;
+use log::debug;
+use rede_schema::auth::{ClientAuth, Grant, OAuth2};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use url::form_urlencoded;
+
+use super::Error;
+use crate::errors::RequestError;
+
+/// File storing the access tokens obtained, relative to the execution directory
+const TOKENS_FILE: &str = ".rede/tokens.toml";
+
+/// Seconds before the expiration at which a token is no longer used, so it can't expire in flight
+const EXPIRATION_MARGIN: u64 = 10;
+
+#[derive(Deserialize, Serialize)]
+struct CachedToken {
+    access_token: String,
+    /// Unix timestamp, in seconds, of the expiration of the token
+    expires_at: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+}
+
+/// Returns the access token to use for the request. The token stored in the tokens file is used
+/// while it's still valid, otherwise a new one is requested to the token endpoint, preferring
+/// the refresh token of the stored one, if any.
+pub(super) async fn access_token(
+    client: &reqwest::Client,
+    oauth2: &OAuth2,
+) -> Result<String, Error> {
+    let key = cache_key(oauth2);
+    let mut tokens = load_tokens();
+    let cached = tokens.remove(&key);
+
+    if let Some(token) = cached
+        .as_ref()
+        .filter(|t| t.expires_at > now() + EXPIRATION_MARGIN)
+    {
+        debug!("using the cached OAuth2 token of {key}");
+        return Ok(token.access_token.clone());
+    }
+
+    let refresh_token = cached.and_then(|t| t.refresh_token);
+    let response = match &refresh_token {
+        Some(refresh_token) => {
+            let grant = Grant::RefreshToken {
+                refresh_token: refresh_token.clone(),
+            };
+            match request_token(client, oauth2, &grant).await {
+                Ok(response) => response,
+                Err(e) => {
+                    debug!("failed to refresh the OAuth2 token: {e}");
+                    request_token(client, oauth2, &oauth2.grant).await?
+                }
+            }
+        }
+        None => request_token(client, oauth2, &oauth2.grant).await?,
+    };
+
+    let access_token = response.access_token;
+    if let Some(expires_in) = response.expires_in {
+        tokens.insert(
+            key,
+            CachedToken {
+                access_token: access_token.clone(),
+                expires_at: now() + expires_in,
+                refresh_token: response.refresh_token.or(refresh_token),
+            },
+        );
+        save_tokens(&tokens);
+    } else {
+        debug!("the OAuth2 token has no expiration, it won't be cached");
+    }
+    Ok(access_token)
+}
+
+/// Removes the stored token of the authentication, to request a new one in the next execution
+pub(super) fn forget(oauth2: &OAuth2) {
+    let mut tokens = load_tokens();
+    if tokens.remove(&cache_key(oauth2)).is_some() {
+        save_tokens(&tokens);
+    }
+}
+
+async fn request_token(
+    client: &reqwest::Client,
+    oauth2: &OAuth2,
+    grant: &Grant,
+) -> Result<TokenResponse, Error> {
+    let url = Url::parse(&oauth2.token_url)
+        .map_err(|e| RequestError::invalid_url(&oauth2.token_url, e))?;
+
+    let grant_type = grant.to_string();
+    let mut form = vec![("grant_type", grant_type.as_str())];
+    if oauth2.client_auth == ClientAuth::Post {
+        form.push(("client_id", &oauth2.client_id));
+        if !oauth2.client_secret.is_empty() {
+            form.push(("client_secret", &oauth2.client_secret));
+        }
+    }
+    if !oauth2.scope.is_empty() {
+        form.push(("scope", &oauth2.scope));
+    }
+    match grant {
+        Grant::ClientCredentials => {}
+        Grant::Password { username, password } => {
+            form.extend([
+                ("username", username.as_str()),
+                ("password", password.as_str()),
+            ]);
+        }
+        Grant::RefreshToken { refresh_token } => form.push(("refresh_token", refresh_token)),
+    }
+
+    debug!("requesting OAuth2 token to {url} with grant {grant_type}");
+    let mut request = client.post(url).header(ACCEPT, "application/json");
+    if oauth2.client_auth == ClientAuth::Basic {
+        // the credentials are form encoded before the Basic encoding, as RFC 6749 requires
+        let encode = |value: &str| form_urlencoded::byte_serialize(value.as_bytes()).collect();
+        let password: String = encode(&oauth2.client_secret);
+        request = request.basic_auth(
+            encode(&oauth2.client_id),
+            Some(password).filter(|p| !p.is_empty()),
+        );
+    }
+    let response = request.form(&form).send().await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(RequestError::oauth2(format!(
+            "the token endpoint responded {status}: {body}"
+        )));
+    }
+    serde_json::from_str(&body)
+        .map_err(|e| RequestError::oauth2(format!("invalid token response, {e}")))
+}
+
+/// The tokens are stored by client, user and scope, as they can grant different permissions
+fn cache_key(oauth2: &OAuth2) -> String {
+    let client = match &oauth2.grant {
+        Grant::Password { username, .. } => format!("{}:{username}", oauth2.client_id),
+        _ => oauth2.client_id.clone(),
+    };
+    format!("{client} {} {}", oauth2.scope, oauth2.token_url)
+}
+
+/// Loads the stored tokens, an unreadable file is treated as empty as the tokens can be requested
+/// again
+fn load_tokens() -> BTreeMap<String, CachedToken> {
+    if !Path::new(TOKENS_FILE).is_file() {
+        return BTreeMap::new();
+    }
+    read_to_string(TOKENS_FILE)
+        .map_err(|e| e.to_string())
+        .and_then(|content| toml::from_str(&content).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            debug!("ignoring the OAuth2 tokens file: {e}");
+            BTreeMap::new()
+        })
+}
+
+/// Stores the tokens in plain text, so in Unix systems the file is only readable by its owner
+fn save_tokens(tokens: &BTreeMap<String, CachedToken>) {
+    let result = toml::to_string(tokens)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            if let Some(dir) = Path::new(TOKENS_FILE).parent() {
+                create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            let mut options = OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            options
+                .open(TOKENS_FILE)
+                .and_then(|mut file| {
+                    // the mode only applies to new files, the existing ones are restricted too
+                    #[cfg(unix)]
+                    {
+                        use std::os::unix::fs::PermissionsExt;
+                        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+                    }
+                    file.write_all(content.as_bytes())
+                })
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        debug!("failed to store the OAuth2 tokens: {e}");
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
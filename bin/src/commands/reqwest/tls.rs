@@ -0,0 +1,66 @@
+use std::fs::read;
+
+use log::debug;
+use rede_schema::tls::{ClientCert, TlsVersion};
+use rede_schema::Tls;
+use reqwest::tls::{Certificate, Identity, Version};
+use reqwest::ClientBuilder;
+
+use super::Error;
+use crate::errors::{root_cause, RequestError};
+
+/// Applies the TLS configuration to the client, loading the certificates of its files
+pub fn configure(mut client: ClientBuilder, tls: &Tls) -> Result<ClientBuilder, Error> {
+    if let Some(path) = &tls.ca_cert {
+        let certs = Certificate::from_pem_bundle(&read_file(path)?)
+            .map_err(|e| RequestError::certificate(path, root_cause(&e)))?;
+        if certs.is_empty() {
+            return Err(RequestError::certificate(path, "no PEM certificate found"));
+        }
+        debug!("trusting {} CA certificates of {path}", certs.len());
+        for cert in certs {
+            client = client.add_root_certificate(cert);
+        }
+    }
+
+    let identity = match &tls.client_cert {
+        Some(ClientCert::Pem { cert, key }) => Some(
+            Identity::from_pkcs8_pem(&read_file(cert)?, &read_file(key)?)
+                .map_err(|e| RequestError::certificate(cert, root_cause(&e)))?,
+        ),
+        Some(ClientCert::Pkcs12 { path, password }) => Some(
+            Identity::from_pkcs12_der(&read_file(path)?, password)
+                .map_err(|e| RequestError::certificate(path, root_cause(&e)))?,
+        ),
+        None => None,
+    };
+    if let Some(identity) = identity {
+        client = client.identity(identity);
+    }
+
+    if let Some(version) = tls.min_version {
+        client = client.min_tls_version(match version {
+            TlsVersion::Tls1_0 => Version::TLS_1_0,
+            TlsVersion::Tls1_1 => Version::TLS_1_1,
+            TlsVersion::Tls1_2 => Version::TLS_1_2,
+            // the native TLS backend can negotiate TLS 1.3, but not require it
+            TlsVersion::Tls1_3 => {
+                return Err(RequestError::TlsVersion {
+                    version: version.to_string(),
+                })
+            }
+        });
+    }
+
+    if tls.insecure {
+        debug!("accepting invalid certificates and hostnames");
+        client = client
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true);
+    }
+    Ok(client)
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, Error> {
+    read(path).map_err(|e| RequestError::io(path, e))
+}
@@ -1,25 +1,44 @@
+mod asserts;
+mod captures;
+mod environment;
 #[cfg(feature = "input_params")]
 mod inputparam_picker;
+mod params;
 mod print;
+mod suite;
 
 use crate::commands::reqwest::Client;
 use crate::commands::RedeCommand;
 use crate::errors::ParsingError;
 use crate::util::input_to_string;
-use clap::{ArgAction, Args};
+use crate::verbose;
+use captures::CapturesPicker;
+use clap::{ArgAction, Args, ValueEnum};
 use console::style;
+use environment::load_environment;
+use http::{HeaderMap, StatusCode};
 #[cfg(feature = "input_params")]
-use inputparam_picker::InputParamPicker;
+use inputparam_picker::{InputDefaultsPicker, InputParamPicker};
+#[cfg(feature = "input_params")]
+use log::debug;
 use log::{info, trace};
 use miette::{miette, LabeledSpan, Report};
-use print::print_replacements;
+use params::{parse_param, ParamsPicker};
+use print::{print_replacements, Secrets};
 use rede_parser::parse_request;
 use rede_placeholders::{
-    value_picker::{EnvVarPicker, VariablesPicker},
+    value_picker::{DynamicPicker, EnvVarPicker, EnvironmentPicker, VariablesPicker},
     Renderer, Resolver,
 };
-use rede_schema::Request;
-use std::time::Duration;
+use rede_schema::body::Compression;
+use rede_schema::tls::{ClientCert, TlsVersion};
+use rede_schema::{Body, Request, Tls};
+#[cfg(feature = "input_params")]
+use std::io::{self, IsTerminal};
+use std::mem::take;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use super::GlobalArgs;
 
@@ -33,6 +52,10 @@ pub struct Command {
     /// Request file to execute
     #[arg(default_value = "-")]
     request: String,
+    #[command(flatten)]
+    placeholders: PlaceholderArgs,
+    #[command(flatten)]
+    tls: TlsArgs,
     /// Specifies if formatting should be applied to response body, by default is true
     #[arg(
         long,
@@ -56,75 +79,278 @@ pub struct Command {
     /// Maximum number of redirects allowed, by default 10.
     #[arg(long)]
     max_redirects: Option<usize>,
-    /// Ignores input params, disabling prompting the user
+    /// Compresses the body of the request with the algorithm: gzip, deflate, br or zstd
+    #[arg(long, value_name = "ALGORITHM", value_parser = Compression::from_str)]
+    compress: Option<Compression>,
+    /// Specifies if compressed responses should be decompressed, by default is true
+    #[arg(
+        long,
+        value_name = "true|false",
+        default_missing_value = "true",
+        num_args(0..=1),
+        require_equals = true,
+        action = ArgAction::Set,
+    )]
+    decompress: Option<bool>,
+    /// File to load the cookies from and to save the received ones to. It uses the Netscape
+    /// format, or JSON if the file ends in .json
+    #[arg(long, value_name = "FILE")]
+    cookie_jar: Option<String>,
+    /// Saves the raw body of the response in the given file instead of printing it. In a suite,
+    /// each body is saved in its own file, named like the request after the given stem
+    #[arg(long, short, value_name = "FILE")]
+    output: Option<String>,
+    /// Format used to print the response
+    #[arg(long, short, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+enum Format {
+    /// Body of the response
+    Text,
+    /// JSON with the status, headers, duration and body of the response
+    Json,
+}
+
+/// Options to resolve the placeholders of a request, shared by the commands rendering requests
+#[derive(Debug, Args)]
+pub(crate) struct PlaceholderArgs {
+    /// Environment to load the placeholder values from, like "staging"
+    #[arg(long, value_name = "NAME")]
+    env: Option<String>,
+    /// Ignores input params, disabling prompting the user. They are always ignored if the input
+    /// is not a terminal
     #[arg(long)]
     no_input: bool,
-    /// Executes the request even if some placeholders ended unresolved
+    /// Renders the request even if some placeholders ended unresolved
     #[arg(long)]
     allow_unresolved: bool,
+    /// Value for a placeholder, like "id=42". It can be repeated
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+    /// File with values for the placeholders, a TOML file or a .env file
+    #[arg(long, value_name = "FILE")]
+    params_file: Option<String>,
+}
+
+/// Options to configure the TLS connection, they take precedence over the `[tls]` table
+#[derive(Debug, Args)]
+#[command(next_help_heading = "TLS")]
+struct TlsArgs {
+    /// PEM file with the CA certificates to trust along with the ones of the system
+    #[arg(long, value_name = "FILE")]
+    cacert: Option<String>,
+    /// PEM file with the client certificate, it requires its key
+    #[arg(long, value_name = "FILE", requires = "key", conflicts_with = "pkcs12")]
+    cert: Option<String>,
+    /// PEM file with the PKCS#8 private key of the client certificate
+    #[arg(long, value_name = "FILE", requires = "cert")]
+    key: Option<String>,
+    /// PKCS#12 archive with the client certificate and its private key
+    #[arg(long, value_name = "FILE")]
+    pkcs12: Option<String>,
+    /// Password of the PKCS#12 archive
+    #[arg(long, value_name = "PASSWORD", requires = "pkcs12")]
+    cert_password: Option<String>,
+    /// Minimum TLS version accepted: 1.0, 1.1 or 1.2
+    #[arg(long, value_name = "VERSION", value_parser = parse_min_tls_version)]
+    min_tls_version: Option<TlsVersion>,
+    /// Accepts invalid certificates and hostnames, use it only for testing
+    #[arg(long, short = 'k')]
+    insecure: bool,
+}
+
+/// Parses the minimum TLS version, rejecting 1.3 as the TLS backend can't require it
+fn parse_min_tls_version(version: &str) -> Result<TlsVersion, String> {
+    match TlsVersion::from_str(version)? {
+        TlsVersion::Tls1_3 => {
+            Err("TLS 1.3 is not supported as minimum version, expected 1.0, 1.1 or 1.2".to_string())
+        }
+        version => Ok(version),
+    }
+}
+
+impl From<&TlsArgs> for Tls {
+    fn from(value: &TlsArgs) -> Self {
+        let client_cert = match (&value.cert, &value.key, &value.pkcs12) {
+            (Some(cert), Some(key), _) => Some(ClientCert::Pem {
+                cert: cert.clone(),
+                key: key.clone(),
+            }),
+            (_, _, Some(path)) => Some(ClientCert::Pkcs12 {
+                path: path.clone(),
+                password: value.cert_password.clone().unwrap_or_default(),
+            }),
+            _ => None,
+        };
+        Tls {
+            ca_cert: value.cacert.clone(),
+            client_cert,
+            min_version: value.min_tls_version,
+            insecure: value.insecure,
+        }
+    }
 }
 
 impl RedeCommand for Command {
     async fn run(self, gargs: GlobalArgs) -> miette::Result<()> {
         info!("Launched rede run with {}", self.request);
 
-        let content = input_to_string(&self.request)?;
+        if Path::new(&self.request).is_dir() {
+            return self.run_suite(&gargs).await;
+        }
+
+        self.execute(&self.request, &gargs).await.map(|_| ())
+    }
+}
+
+impl Command {
+    /// Executes the request of the given file returning the status of the response and the time
+    /// it took to arrive, if any
+    async fn execute(
+        &self,
+        filename: &str,
+        gargs: &GlobalArgs,
+    ) -> miette::Result<Option<(StatusCode, Duration)>> {
+        let content = input_to_string(filename)?;
         trace!("Content: {content}");
 
         let request = parse_request(&content).map_err(|e| ParsingError::parsing(content, e))?;
-        let request = self.replace_placeholders(request)?;
+        let (mut request, secrets) = self.placeholders.render(request)?;
 
-        self.print_request(&request);
+        Self::print_request(&request, filename, &secrets);
         if gargs.dry_run {
-            return Ok(());
+            return Ok(None);
         }
 
-        let client = Client::new((&self).try_into()?);
+        let asserts = take(&mut request.asserts);
+        let captures = take(&mut request.captures);
+        let mut properties = ClientProperties::try_from(self)?;
+        if let (None, Some(decompress)) = (self.decompress, request.decompress) {
+            properties.decompress = decompress;
+        }
+        properties.tls = take(&mut properties.tls).or(take(&mut request.tls));
+        let decompress = properties.decompress;
+        if self.compress.is_some() {
+            request.compression = self.compress;
+        }
+        let client = Client::new(properties);
+        let graphql = matches!(request.body, Body::GraphQL(_));
 
+        let start = Instant::now();
         let response = client.send(request);
-        let bar = self.new_spinner();
+        let bar = Self::new_spinner(filename);
         let response = response.await?;
+        let elapsed = start.elapsed();
         bar.finish_and_clear();
-        self.print_response(response).await;
 
-        Ok(())
+        let status = response.status();
+        let headers = response.headers().clone();
+        let output = self.output_file(filename);
+        let body = self
+            .print_response(response, elapsed, decompress, output.as_deref(), &secrets)
+            .await?;
+        if graphql {
+            self.print_graphql_errors(body.as_deref());
+        }
+
+        let summary = ResponseSummary {
+            status,
+            headers: &headers,
+            body: body.as_deref(),
+            elapsed,
+        };
+        if !asserts.is_empty() {
+            // the captures of a response that fails its asserts are not saved
+            asserts::check(&asserts, &summary)?;
+            verbose!(
+                "\n{} All the asserts passed ({})",
+                style("✓").bold().green(),
+                asserts.len()
+            );
+        }
+        if !captures.is_empty() {
+            captures::capture(&captures, &summary)?;
+        }
+
+        Ok(Some((status, elapsed)))
     }
 }
 
-impl Command {
-    fn replace_placeholders(&self, request: Request) -> miette::Result<Request> {
+impl PlaceholderArgs {
+    /// Resolves the placeholders of the request and renders it with the resolved values
+    pub(crate) fn replace_placeholders(&self, request: Request) -> miette::Result<Request> {
+        self.render(request).map(|(request, _)| request)
+    }
+
+    /// Renders the request like [`Self::replace_placeholders`], also returning the values of the
+    /// secret input params to mask them in the output
+    fn render(&self, request: Request) -> miette::Result<(Request, Secrets)> {
         let placeholders = (&request).into();
+        let environment = self.env.as_deref().map(load_environment).transpose()?;
         let values = {
-            let resolver = Resolver::new().add_picker(Box::new(EnvVarPicker));
+            let params = ParamsPicker::load(&self.params, self.params_file.as_deref())?;
             #[cfg(feature = "input_params")]
-            let resolver = if self.no_input {
+            params.check(&request.input_params)?;
+            let resolver = Resolver::new()
+                .add_picker(Box::new(params))
+                .add_picker(Box::new(DynamicPicker))
+                .add_picker(Box::new(EnvVarPicker));
+            let resolver = if let Some(environment) = environment {
+                resolver.add_picker(Box::new(EnvironmentPicker::new(environment)))
+            } else {
                 resolver
+            };
+            let resolver = resolver.add_picker(Box::new(CapturesPicker::load()?));
+            #[cfg(feature = "input_params")]
+            let resolver = if self.no_input || !io::stdin().is_terminal() {
+                debug!("Input params disabled, the input is not a terminal or --no-input is set");
+                resolver.add_picker(Box::new(InputDefaultsPicker::new(&request.input_params)))
             } else {
                 resolver.add_picker(Box::new(InputParamPicker::new(&request.input_params)))
             };
             let resolver = resolver.add_picker(Box::new(VariablesPicker::new(&request.variables)));
-            resolver.resolve(&placeholders)
+            resolver.resolve(&placeholders)?
         };
 
-        print_replacements(&values);
-        if values.is_all_resolved() || self.allow_unresolved {
-            Renderer::new(&placeholders, values).render(request)
+        let secrets = Secrets::new(&request, &values);
+        print_replacements(&values, &secrets);
+        let unresolved = values
+            .unresolved()
+            .filter(|key| !placeholders.has_default(key))
+            .collect::<Vec<&str>>();
+        if unresolved.is_empty() || self.allow_unresolved {
+            Renderer::new(&placeholders, values)
+                .render(request)
+                .map(|request| (request, secrets))
         } else {
             Err(miette!(
             code = "unresolved placeholders",
             url = "https://rede.sotoestevez.dev/reference_guide/request_dsl/placeholders.html",
             help = "check the placeholders of you request or ensure than you input all those without variable",
             "At least one of you placeholders ended unresolved: {}",
-            values.unresolved().collect::<Vec<&str>>().join(", ")
+            unresolved.join(", ")
         ))
         }
     }
 }
 
+/// Data of the response used to process the asserts and captures
+pub(crate) struct ResponseSummary<'r> {
+    pub status: StatusCode,
+    pub headers: &'r HeaderMap,
+    pub body: Option<&'r str>,
+    pub elapsed: Duration,
+}
+
 pub struct ClientProperties {
     pub timeout: Option<Duration>,
     pub no_redirect: bool,
     pub max_redirects: Option<usize>,
+    pub decompress: bool,
+    pub cookie_jar: Option<String>,
+    pub tls: Tls,
 }
 
 impl TryFrom<&Command> for ClientProperties {
@@ -152,6 +378,9 @@ impl TryFrom<&Command> for ClientProperties {
             timeout,
             no_redirect: value.no_redirect,
             max_redirects: value.max_redirects,
+            decompress: value.decompress.unwrap_or(true),
+            cookie_jar: value.cookie_jar.clone(),
+            tls: Tls::from(&value.tls),
         })
     }
 }
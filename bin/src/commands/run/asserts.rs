@@ -0,0 +1,250 @@
+use rede_schema::Asserts;
+use serde_json::Value;
+
+use super::ResponseSummary;
+use crate::errors::{AssertsError, FailedAssert};
+
+/// Checks all the asserts against the response returning an error with all the failed ones
+pub(super) fn check(asserts: &Asserts, response: &ResponseSummary) -> Result<(), AssertsError> {
+    let mut failures = Vec::new();
+
+    if let Some(expected) = asserts.status {
+        if expected != response.status {
+            failures.push(FailedAssert::Status {
+                expected,
+                actual: response.status,
+            });
+        }
+    }
+
+    for (name, header_assert) in &asserts.headers {
+        let actual = response
+            .headers
+            .get(name.as_str())
+            .and_then(|v| v.to_str().ok());
+        if !actual.is_some_and(|v| header_assert.is_satisfied_by(v)) {
+            failures.push(FailedAssert::Header {
+                name: name.clone(),
+                expected: header_assert.to_string(),
+                actual: actual.map(str::to_string),
+            });
+        }
+    }
+
+    if !asserts.body.is_empty() {
+        let json = response
+            .body
+            .and_then(|body| serde_json::from_str::<Value>(body).ok());
+        for (path, expected) in &asserts.body {
+            let actual = json
+                .as_ref()
+                .zip(json_pointer(path))
+                .and_then(|(json, pointer)| json.pointer(&pointer))
+                .map(|value| match value {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                });
+            if actual.as_ref() != Some(expected) {
+                failures.push(FailedAssert::Body {
+                    path: path.clone(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+
+    if let Some(max) = asserts.max_response_time {
+        if response.elapsed >= max {
+            failures.push(FailedAssert::ResponseTime {
+                max: max.as_millis(),
+                actual: response.elapsed.as_millis(),
+            });
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(AssertsError {
+            total: asserts.len(),
+            failures,
+        })
+    }
+}
+
+/// Converts the supported subset of the JSON path syntax, like `$.key.nested[0]` or `$['key']`,
+/// into the equivalent JSON pointer. Returns `None` if the path is not supported.
+fn json_pointer(path: &str) -> Option<String> {
+    let mut rest = path.strip_prefix('$')?;
+    let mut pointer = String::new();
+    while !rest.is_empty() {
+        let (token, next) = if let Some(key) = rest.strip_prefix('.') {
+            let end = key.find(['.', '[']).unwrap_or(key.len());
+            (&key[..end], &key[end..])
+        } else if let Some(index) = rest.strip_prefix('[') {
+            let end = index.find(']')?;
+            let token = index[..end].trim_matches(['\'', '"']);
+            (token, &index[end + 1..])
+        } else {
+            return None;
+        };
+        if token.is_empty() {
+            return None;
+        }
+        pointer.push('/');
+        pointer.push_str(&token.replace('~', "~0").replace('/', "~1"));
+        rest = next;
+    }
+    Some(pointer)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use http::{HeaderMap, HeaderValue, StatusCode};
+    use rede_schema::asserts::HeaderAssert;
+
+    use super::*;
+
+    const BODY: &str = r#"{"id":7,"user":{"name":"rede","tags":["a","b"]},"a/b":true}"#;
+
+    fn response(headers: &HeaderMap) -> ResponseSummary<'_> {
+        ResponseSummary {
+            status: StatusCode::OK,
+            headers,
+            body: Some(BODY),
+            elapsed: Duration::from_millis(100),
+        }
+    }
+
+    #[test]
+    fn status() {
+        let headers = HeaderMap::new();
+        let mut asserts = Asserts {
+            status: Some(StatusCode::OK),
+            ..Default::default()
+        };
+        assert!(check(&asserts, &response(&headers)).is_ok());
+        asserts.status = Some(StatusCode::CREATED);
+        let err = check(&asserts, &response(&headers)).unwrap_err();
+        assert!(matches!(
+            err.failures[..],
+            [FailedAssert::Status {
+                expected: StatusCode::CREATED,
+                actual: StatusCode::OK
+            }]
+        ));
+    }
+
+    #[test]
+    fn headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        let mut asserts = Asserts::default();
+        asserts.headers.insert(
+            "Content-Type".to_string(),
+            HeaderAssert::Contains("json".to_string()),
+        );
+        assert!(check(&asserts, &response(&headers)).is_ok());
+        asserts.headers.insert(
+            "content-type".to_string(),
+            HeaderAssert::Equals("text/plain".to_string()),
+        );
+        asserts.headers.insert(
+            "x-missing".to_string(),
+            HeaderAssert::Contains(String::new()),
+        );
+        let err = check(&asserts, &response(&headers)).unwrap_err();
+        assert_eq!(err.total, 3);
+        let failures = err
+            .failures
+            .iter()
+            .map(|f| match f {
+                FailedAssert::Header { name, actual, .. } => (name.as_str(), actual.as_deref()),
+                other => panic!("unexpected failure {other:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            failures,
+            [
+                ("content-type", Some("application/json")),
+                ("x-missing", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn body() {
+        let headers = HeaderMap::new();
+        let mut asserts = Asserts::default();
+        for (path, expected) in [
+            ("$.id", "7"),
+            ("$.user.name", "rede"),
+            ("$.user.tags[1]", "b"),
+            ("$['user']['tags'][0]", "a"),
+            ("$['a/b']", "true"),
+        ] {
+            asserts.body.insert(path.to_string(), expected.to_string());
+        }
+        assert!(check(&asserts, &response(&headers)).is_ok());
+
+        asserts.body.clear();
+        asserts.body.insert("$.id".to_string(), "8".to_string());
+        asserts
+            .body
+            .insert("$.missing".to_string(), "x".to_string());
+        let err = check(&asserts, &response(&headers)).unwrap_err();
+        let failures = err
+            .failures
+            .iter()
+            .map(|f| match f {
+                FailedAssert::Body { path, actual, .. } => (path.as_str(), actual.as_deref()),
+                other => panic!("unexpected failure {other:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(failures, [("$.id", Some("7")), ("$.missing", None)]);
+
+        let text = ResponseSummary {
+            body: Some("not json"),
+            ..response(&headers)
+        };
+        assert_eq!(check(&asserts, &text).unwrap_err().failures.len(), 2);
+    }
+
+    #[test]
+    fn response_time() {
+        let headers = HeaderMap::new();
+        let mut asserts = Asserts {
+            max_response_time: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        assert!(check(&asserts, &response(&headers)).is_ok());
+        asserts.max_response_time = Some(Duration::from_millis(50));
+        let err = check(&asserts, &response(&headers)).unwrap_err();
+        assert!(matches!(
+            err.failures[..],
+            [FailedAssert::ResponseTime {
+                max: 50,
+                actual: 100
+            }]
+        ));
+        // the response must arrive in less than the maximum
+        asserts.max_response_time = Some(Duration::from_millis(100));
+        assert!(check(&asserts, &response(&headers)).is_err());
+    }
+
+    #[test]
+    fn json_paths() {
+        assert_eq!(json_pointer("$").as_deref(), Some(""));
+        assert_eq!(json_pointer("$.a.b").as_deref(), Some("/a/b"));
+        assert_eq!(json_pointer("$.a[0].b").as_deref(), Some("/a/0/b"));
+        assert_eq!(json_pointer("$['a.b'][\"c\"]").as_deref(), Some("/a.b/c"));
+        assert_eq!(json_pointer("$['a/b~c']").as_deref(), Some("/a~1b~0c"));
+        assert_eq!(json_pointer("a.b"), None);
+        assert_eq!(json_pointer("$.a[0"), None);
+        assert_eq!(json_pointer("$..a"), None);
+        assert_eq!(json_pointer("$a"), None);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+
+use console::style;
+use log::debug;
+use miette::miette;
+use rede_parser::parse_environment;
+use rede_placeholders::ValuePicker;
+use rede_schema::Capture;
+use regex::Regex;
+use serde_json::Value;
+
+use super::ResponseSummary;
+use crate::errors::ParsingError;
+use crate::util::input_to_string;
+use crate::{standard, verbose};
+
+/// File storing the values captured from the responses, relative to the execution directory
+const CAPTURES_FILE: &str = ".rede/captures.toml";
+
+/// [`ValuePicker`](rede_placeholders::ValuePicker) implementation to obtain the values captured
+/// from the responses of previous requests.
+pub(super) struct CapturesPicker {
+    values: BTreeMap<String, String>,
+}
+
+impl ValuePicker for CapturesPicker {
+    fn pick_for(&self, placeholder: &str) -> Option<String> {
+        self.values.get(placeholder).map(String::to_owned)
+    }
+}
+
+impl CapturesPicker {
+    /// Loads the picker with the values of the captures file, if any
+    pub fn load() -> miette::Result<Self> {
+        Ok(Self {
+            values: load_captures()?,
+        })
+    }
+}
+
+/// Extracts the captures from the response and stores them in the captures file, along with those
+/// already stored. The captures that can't be extracted are reported and skipped.
+pub(super) fn capture(
+    captures: &BTreeMap<String, Capture>,
+    response: &ResponseSummary,
+) -> miette::Result<()> {
+    verbose!("\n{} Captures", style(">").bold().blue());
+
+    let json = response
+        .body
+        .and_then(|body| serde_json::from_str::<Value>(body).ok());
+    let mut values = load_captures()?;
+    for (key, capture) in captures {
+        if let Some(value) = extract(capture, response, json.as_ref()) {
+            verbose!("    {key} {} {value}", style(">").bold().green());
+            values.insert(key.clone(), value);
+        } else {
+            standard!(
+                " {} Failed to capture {} from {capture}",
+                style("x").red().bold(),
+                style(key).yellow()
+            );
+        }
+    }
+
+    let content = toml::to_string(&values)
+        .map_err(|e| miette!("There was a problem serializing the captures: {e}"))?;
+    if let Some(dir) = Path::new(CAPTURES_FILE).parent() {
+        create_dir_all(dir)
+            .map_err(|e| miette!("There was a problem creating the captures file: {e}"))?;
+    }
+    write(CAPTURES_FILE, content)
+        .map_err(|e| miette!("There was a problem saving the captures: {e}"))
+}
+
+fn extract(capture: &Capture, response: &ResponseSummary, json: Option<&Value>) -> Option<String> {
+    match capture {
+        Capture::Json(pointer) => json?.pointer(pointer).map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }),
+        Capture::Header(name) => response
+            .headers
+            .get(name.as_str())
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        Capture::Regex(regex) => {
+            // the parser rejects the invalid patterns, so they are only skipped here
+            let regex = Regex::new(regex)
+                .inspect_err(|e| debug!("invalid capture regex: {e}"))
+                .ok()?;
+            let captures = regex.captures(response.body?)?;
+            captures
+                .get(1)
+                .or_else(|| captures.get(0))
+                .map(|m| m.as_str().to_string())
+        }
+        Capture::Status => Some(response.status.as_u16().to_string()),
+    }
+}
+
+fn load_captures() -> miette::Result<BTreeMap<String, String>> {
+    if !Path::new(CAPTURES_FILE).is_file() {
+        return Ok(BTreeMap::new());
+    }
+    let content = input_to_string(CAPTURES_FILE)?;
+    Ok(parse_environment(&content).map_err(|e| ParsingError::parsing(content, e))?)
+}
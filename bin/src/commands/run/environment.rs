@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use log::debug;
+use rede_parser::{parse_environment, parse_environments};
+
+use crate::errors::ParsingError;
+use crate::util::input_to_string;
+
+/// File containing multiple environments, each one defined in its own table
+const ENVIRONMENTS_FILE: &str = "rede.env.toml";
+/// Directory containing one file per environment, named after the environment
+const ENVIRONMENTS_DIR: &str = ".rede/envs";
+
+/// Loads the values of the environment with the given name. The environment is first searched
+/// as its own file, `.rede/envs/<name>.toml`, and then as a table of the `rede.env.toml` file.
+/// Both paths are relative to the directory where `rede` is being executed.
+pub(super) fn load_environment(name: &str) -> miette::Result<BTreeMap<String, String>> {
+    let path = Path::new(ENVIRONMENTS_DIR).join(format!("{name}.toml"));
+    if path.is_file() {
+        debug!("Loading environment {name} from {}", path.display());
+        let content = input_to_string(&path.to_string_lossy())?;
+        return Ok(parse_environment(&content).map_err(|e| ParsingError::parsing(content, e))?);
+    }
+
+    if Path::new(ENVIRONMENTS_FILE).is_file() {
+        debug!("Loading environment {name} from {ENVIRONMENTS_FILE}");
+        let content = input_to_string(ENVIRONMENTS_FILE)?;
+        let mut environments =
+            parse_environments(&content).map_err(|e| ParsingError::parsing(content, e))?;
+        if let Some(environment) = environments.remove(name) {
+            return Ok(environment);
+        }
+    }
+
+    Err(ParsingError::unknown_environment(name).into())
+}
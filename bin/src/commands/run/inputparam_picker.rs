@@ -1,9 +1,12 @@
 use std::collections::BTreeMap;
 
 use console::style;
-use dialoguer::Input;
+use dialoguer::{Input, Password, Select};
+use log::debug;
 use rede_placeholders::ValuePicker;
-use rede_schema::InputParam;
+use rede_schema::{InputParam, InputType};
+use regex::Regex;
+use url::Url;
 
 use crate::verbose;
 
@@ -13,7 +16,7 @@ pub(super) struct InputParamPicker<'req> {
     input_params: &'req BTreeMap<String, InputParam>,
 }
 
-impl<'req> ValuePicker for InputParamPicker<'req> {
+impl ValuePicker for InputParamPicker<'_> {
     fn pick_for(&self, placeholder: &str) -> Option<String> {
         if let Some(ip) = self.input_params.get(placeholder) {
             let hint = if let Some(hint) = &ip.hint {
@@ -27,11 +30,37 @@ impl<'req> ValuePicker for InputParamPicker<'req> {
                 style(placeholder).bold(),
                 hint
             );
-            let value: String = Input::new()
-                .with_prompt(&prompt)
-                .allow_empty(true)
+
+            if !ip.choices.is_empty() {
+                return select(&prompt, ip);
+            }
+
+            let validator = Validator::new(&ip.kind);
+            let value: String = if ip.secret {
+                let value = Password::new()
+                    .with_prompt(&prompt)
+                    .allow_empty_password(true)
+                    .validate_with(|input: &String| validator.validate(input))
+                    .interact()
+                    .inspect_err(|e| debug!("failed to prompt for {placeholder}: {e}"))
+                    .ok()?;
+                match &ip.default {
+                    Some(default) if value.is_empty() => default.clone(),
+                    _ => value,
+                }
+            } else {
+                let input = Input::new()
+                    .with_prompt(&prompt)
+                    .allow_empty(true)
+                    .validate_with(|input: &String| validator.validate(input));
+                match &ip.default {
+                    Some(default) => input.default(default.clone()),
+                    None => input,
+                }
                 .interact()
-                .unwrap();
+                .inspect_err(|e| debug!("failed to prompt for {placeholder}: {e}"))
+                .ok()?
+            };
 
             if value.is_empty() {
                 verbose!("{}", style("  Using default if any"));
@@ -48,3 +77,184 @@ impl<'req> InputParamPicker<'req> {
         Self { input_params }
     }
 }
+
+/// [`ValuePicker`](rede_placeholders::value_picker::ValuePicker) implementation to obtain the
+/// default values of the input params, used in place of the [`InputParamPicker`] when the user
+/// can't be prompted.
+pub(super) struct InputDefaultsPicker<'req> {
+    input_params: &'req BTreeMap<String, InputParam>,
+}
+
+impl ValuePicker for InputDefaultsPicker<'_> {
+    fn pick_for(&self, placeholder: &str) -> Option<String> {
+        self.input_params.get(placeholder)?.default.clone()
+    }
+}
+
+impl<'req> InputDefaultsPicker<'req> {
+    pub fn new(input_params: &'req BTreeMap<String, InputParam>) -> Self {
+        Self { input_params }
+    }
+}
+
+/// Checks that a value given to the input param by other means than the prompt, like `--param`,
+/// is valid for it, as the ones typed by the user
+pub(super) fn check_value(ip: &InputParam, value: &str) -> Result<(), String> {
+    if !ip.choices.is_empty() && !ip.choices.iter().any(|c| c == value) {
+        return Err(format!(
+            "The value must be one of {}",
+            ip.choices.join(", ")
+        ));
+    }
+    Validator::new(&ip.kind).validate(value)
+}
+
+/// Asks the user to select one of the choices, the default one is selected initially. Escaping
+/// the selection, or failing to prompt, leaves the value to the next pickers.
+fn select(prompt: &str, ip: &InputParam) -> Option<String> {
+    let default = ip
+        .default
+        .as_ref()
+        .and_then(|default| ip.choices.iter().position(|c| c == default))
+        .unwrap_or_default();
+    let selected = Select::new()
+        .with_prompt(prompt)
+        .items(&ip.choices)
+        .default(default)
+        .interact_opt()
+        .inspect_err(|e| debug!("failed to prompt for the choices: {e}"))
+        .ok()
+        .flatten();
+    if selected.is_none() {
+        verbose!("{}", style("  Using default if any"));
+    }
+    selected.map(|i| ip.choices[i].clone())
+}
+
+/// Checks that the inputs are valid for the type of the input param. Empty inputs are always
+/// valid, as they leave the value to the next pickers.
+enum Validator {
+    Type(&'static str, fn(&str) -> bool),
+    /// The whole input must match the pattern, which is kept to show it to the user
+    Regex(Regex, String),
+    Any,
+}
+
+impl Validator {
+    fn new(kind: &InputType) -> Self {
+        match kind {
+            InputType::String => Validator::Any,
+            InputType::Int => Validator::Type("an integer", |v| v.parse::<i64>().is_ok()),
+            InputType::Float => Validator::Type("a number", |v| v.parse::<f64>().is_ok()),
+            InputType::Bool => Validator::Type("true or false", |v| v.parse::<bool>().is_ok()),
+            InputType::Url => Validator::Type("an absolute URL", |v| Url::parse(v).is_ok()),
+            InputType::Email => Validator::Type("an email address", is_email),
+            // the parser rejects the invalid patterns, so they are only ignored here
+            InputType::Regex(pattern) => Regex::new(&format!("^(?:{pattern})$"))
+                .inspect_err(|e| debug!("ignoring invalid input param regex: {e}"))
+                .map_or(Validator::Any, |regex| {
+                    Validator::Regex(regex, pattern.clone())
+                }),
+        }
+    }
+
+    fn validate(&self, input: &str) -> Result<(), String> {
+        if input.is_empty() {
+            return Ok(());
+        }
+        match self {
+            Validator::Type(expected, is_valid) if !is_valid(input) => {
+                Err(format!("The value must be {expected}"))
+            }
+            Validator::Regex(regex, pattern) if !regex.is_match(input) => {
+                Err(format!("The value must match {pattern}"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_email(value: &str) -> bool {
+    let Some((user, domain)) = value.split_once('@') else {
+        return false;
+    };
+    !user.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !value.contains(char::is_whitespace)
+        && !domain.contains('@')
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validate_types() {
+        let int = Validator::new(&InputType::Int);
+        assert_eq!(int.validate("42"), Ok(()));
+        assert_eq!(int.validate(""), Ok(()));
+        assert_eq!(
+            int.validate("4.2"),
+            Err("The value must be an integer".to_string())
+        );
+        assert!(Validator::new(&InputType::Float).validate("4.2").is_ok());
+        assert!(Validator::new(&InputType::Bool).validate("yes").is_err());
+        let url = Validator::new(&InputType::Url);
+        assert!(url.validate("https://example.org/path").is_ok());
+        assert!(url.validate("/path").is_err());
+        assert!(Validator::new(&InputType::String).validate("any").is_ok());
+    }
+
+    #[test]
+    fn validate_regex() {
+        let validator = Validator::new(&InputType::Regex("v[0-9]+".to_string()));
+        assert_eq!(validator.validate("v12"), Ok(()));
+        assert_eq!(
+            validator.validate("version v12"),
+            Err("The value must match v[0-9]+".to_string())
+        );
+        assert!(validator.validate("v12b").is_err());
+        let alternatives = Validator::new(&InputType::Regex("a|b".to_string()));
+        assert!(alternatives.validate("b").is_ok());
+        assert!(alternatives.validate("ab").is_err());
+        let invalid = Validator::new(&InputType::Regex("v[0-9".to_string()));
+        assert!(invalid.validate("anything").is_ok());
+    }
+
+    #[test]
+    fn check_values() {
+        let ip = InputParam {
+            kind: InputType::Int,
+            ..Default::default()
+        };
+        assert_eq!(check_value(&ip, "42"), Ok(()));
+        assert_eq!(
+            check_value(&ip, "abc"),
+            Err("The value must be an integer".to_string())
+        );
+        let ip = InputParam {
+            choices: vec!["dev".to_string(), "prod".to_string()],
+            ..Default::default()
+        };
+        assert!(check_value(&ip, "dev").is_ok());
+        assert_eq!(
+            check_value(&ip, "staging"),
+            Err("The value must be one of dev, prod".to_string())
+        );
+    }
+
+    #[test]
+    fn emails() {
+        assert!(is_email("user@example.org"));
+        assert!(is_email("first.last+tag@mail.example.org"));
+        assert!(!is_email("user"));
+        assert!(!is_email("@example.org"));
+        assert!(!is_email("user@localhost"));
+        assert!(!is_email("user@.example.org"));
+        assert!(!is_email("user@example.org."));
+        assert!(!is_email("us er@example.org"));
+        assert!(!is_email("user@exa@mple.org"));
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use console::style;
+use log::debug;
+use miette::{miette, LabeledSpan};
+use rede_parser::parse_environment;
+use rede_placeholders::ValuePicker;
+#[cfg(feature = "input_params")]
+use rede_schema::InputParam;
+
+#[cfg(feature = "input_params")]
+use super::inputparam_picker::check_value;
+
+use crate::errors::ParsingError;
+
+/// [`ValuePicker`](rede_placeholders::ValuePicker) implementation to obtain the values given
+/// through the command line, with `--param` and `--params-file`.
+pub(super) struct ParamsPicker {
+    values: BTreeMap<String, String>,
+}
+
+impl ValuePicker for ParamsPicker {
+    fn pick_for(&self, placeholder: &str) -> Option<String> {
+        self.values.get(placeholder).map(String::to_owned)
+    }
+}
+
+impl ParamsPicker {
+    /// Loads the values of the params file, if any, overridden by the given params
+    pub fn load(params: &[(String, String)], file: Option<&str>) -> miette::Result<Self> {
+        let mut values = match file {
+            Some(file) => load_file(file)?,
+            None => BTreeMap::new(),
+        };
+        values.extend(params.iter().cloned());
+        Ok(Self { values })
+    }
+
+    /// Checks the values given to the input params of the request, as they skip the prompt
+    #[cfg(feature = "input_params")]
+    pub fn check(&self, input_params: &BTreeMap<String, InputParam>) -> miette::Result<()> {
+        for (name, value) in &self.values {
+            let Some(ip) = input_params.get(name) else {
+                continue;
+            };
+            check_value(ip, value).map_err(|reason| {
+                miette!(
+                    code = "invalid param value",
+                    url = "https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-param-value",
+                    help = reason,
+                    "The value given to the input param {} is not valid",
+                    style(name).yellow()
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the `key=value` pairs given with `--param`
+pub(super) fn parse_param(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected key=value, found `{param}`")),
+    }
+}
+
+/// Loads the values of a `.env` file or, with any other name, of a TOML file
+fn load_file(file: &str) -> miette::Result<BTreeMap<String, String>> {
+    let content = read_to_string(file).map_err(|e| {
+        miette!(
+            code = "invalid params file",
+            url = "https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-params-file",
+            help = "check if the file name is correct or you're in the correct path",
+            "Failed to read the params file {}: {e}",
+            style(file).yellow()
+        )
+    })?;
+    let path = Path::new(file);
+    let is_dotenv = path.file_name().is_some_and(|name| name == ".env")
+        || path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("env"));
+    if is_dotenv {
+        debug!("Loading params from the dotenv file {file}");
+        parse_dotenv(content)
+    } else {
+        debug!("Loading params from the TOML file {file}");
+        Ok(parse_environment(&content).map_err(|e| ParsingError::parsing(content, e))?)
+    }
+}
+
+/// Parses the `KEY=value` lines of a dotenv file, ignoring the empty ones and the comments. The
+/// `export` prefix and the quotes surrounding the values are removed.
+fn parse_dotenv(content: String) -> miette::Result<BTreeMap<String, String>> {
+    let mut values = BTreeMap::new();
+    let mut offset = 0;
+    // the offsets of the lines take into account their actual endings, `\n` or `\r\n`
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        let end = start + line.len();
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(miette!(
+                code = "invalid params file",
+                help = "each line of a .env file must be like KEY=value",
+                labels = vec![LabeledSpan::at(start..end, "here")],
+                "Failed to parse the params file"
+            )
+            .with_source_code(content));
+        };
+        let value = value.trim();
+        let value = ['"', '\'']
+            .iter()
+            .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+            .unwrap_or(value);
+        values.insert(key.trim().to_string(), value.to_string());
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dotenv() {
+        let content = "# comment\nexport TOKEN=\"abc\"\n\nID = 42\nNAME='rede'";
+        let values = parse_dotenv(content.to_string()).unwrap();
+        assert_eq!(values["TOKEN"], "abc");
+        assert_eq!(values["ID"], "42");
+        assert_eq!(values["NAME"], "rede");
+    }
+
+    #[test]
+    fn dotenv_error_span() {
+        for content in ["ID=1\nTOKEN=2\nbroken\n", "ID=1\r\nTOKEN=2\r\nbroken\r\n"] {
+            let err = parse_dotenv(content.to_string()).unwrap_err();
+            let label = err.labels().unwrap().next().unwrap();
+            let span = label.offset()..label.offset() + label.len();
+            assert_eq!(&content[span], "broken");
+        }
+    }
+}
@@ -1,24 +1,42 @@
+use super::Format;
+use crate::terminal::print;
+use crate::util::decompress;
 use crate::{if_mode, standard, verbose};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use console::{style, Style};
+use http::header::CONTENT_ENCODING;
 use http::{HeaderMap, Method, StatusCode};
 use indicatif::{ProgressBar, ProgressStyle};
 use log::{debug, error};
+use miette::miette;
 use rede_placeholders::PlaceholderValues;
 use rede_schema::{Body, Request};
 use reqwest::Response;
-use serde_json::{from_str, to_string_pretty};
+use serde_json::{from_str, json, to_string, to_string_pretty, Map, Value};
+use std::fs::{create_dir_all, write};
+use std::path::Path;
 use std::time::Duration;
 
+/// Text printed in place of the secret values
+const MASK: &str = "********";
+
 const SPINNER_TEMPLATE: &str = "{prefix} {spinner:.cyan/blue} Waiting for the response: {elapsed}";
 
 impl super::Command {
-    pub(crate) fn print_request(&self, request: &Request) {
-        debug!("{request:?}");
+    pub(crate) fn print_request(request: &Request, filename: &str, secrets: &Secrets) {
+        debug!("{}", secrets.mask(&format!("{request:?}")));
 
         verbose!(
             "{} Executing request {}\n",
             style(">").bold().blue(),
-            style(request.metadata.get("name").unwrap_or(&self.request)).yellow()
+            style(
+                request
+                    .metadata
+                    .get("name")
+                    .map_or(filename, String::as_str)
+            )
+            .yellow()
         );
 
         let output_arrows = style(">>>").bold().blue();
@@ -41,37 +59,85 @@ impl super::Command {
             format!("?{query}")
         };
 
-        let url = format!("{}{}", request.url, query);
+        let url = secrets.mask(&format!("{}{}", request.url, query));
         let method = method_style(&request.method).apply_to(request.method.as_str());
         verbose!("{method} {}", style(url).underlined().blue(),);
         verbose!("{:?}", request.http_version);
 
-        print_headers(&request.headers);
+        print_headers(&request.headers, secrets);
+
+        if !request.cookies.is_empty() {
+            let cookies = request
+                .cookies
+                .iter()
+                .map(|(k, v)| format!("{k}={}", secrets.mask(v)))
+                .collect::<Vec<String>>()
+                .join("; ");
+            verbose!("{} {cookies}\n", style("Cookies").cyan());
+        }
+
+        if !request.auth.is_none() {
+            let auth = secrets.mask(&request.auth.to_string());
+            verbose!("{} {auth}\n", style("Auth").cyan());
+        }
 
         if let Some(mime) = request.body.mime() {
             verbose!("[{}]", style(mime).cyan());
         }
         match &request.body {
-            Body::Raw { content, .. } => verbose!("{content}"),
+            Body::Raw { content, .. } => verbose!("{}", secrets.mask(content)),
+            Body::Json(value) => verbose!("{}", secrets.mask(&format!("{value:#}"))),
             Body::Binary { path, .. } => verbose!("    @{path}"),
+            Body::GraphQL(graphql) => verbose!("{}", secrets.mask(&graphql.to_string())),
             Body::XFormUrlEncoded(map) => {
                 let query = map
                     .iter()
-                    .map(|(k, v)| format!("{k}={v}"))
+                    .map(|(k, v)| format!("{k}={}", secrets.mask(v)))
                     .collect::<Vec<String>>()
                     .join(&style("&").blue().to_string());
                 verbose!("{query}");
             }
             Body::FormData(form) => {
                 for (k, v) in form {
-                    verbose!("{}: {}", style(k).blue(), v);
+                    verbose!("{}: {}", style(k).blue(), secrets.mask(&v.to_string()));
                 }
             }
             Body::None => {}
         }
     }
 
-    pub(crate) async fn print_response(&self, response: Response) {
+    /// Returns the file to save the body of the response of the request, if any. The requests of
+    /// a suite use their own file, like `output.request.json` for the request `request.toml`, so
+    /// they don't overwrite each other.
+    pub(crate) fn output_file(&self, filename: &str) -> Option<String> {
+        let output = self.output.as_ref()?;
+        if !Path::new(&self.request).is_dir() {
+            return Some(output.clone());
+        }
+        let request = Path::new(filename).file_stem()?.to_string_lossy();
+        let path = Path::new(output);
+        let file = match (path.file_stem(), path.extension()) {
+            (Some(stem), Some(ext)) => format!(
+                "{}.{request}.{}",
+                stem.to_string_lossy(),
+                ext.to_string_lossy()
+            ),
+            _ => format!("{output}.{request}"),
+        };
+        Some(path.with_file_name(file).to_string_lossy().to_string())
+    }
+
+    /// Prints the response, or saves its body in the output file, and returns its body, if it's
+    /// text. Compressed bodies are decompressed first, unless it's disabled. The JSON format is
+    /// always printed, whatever the verbosity, as it's meant to be consumed by other programs.
+    pub(crate) async fn print_response(
+        &self,
+        response: Response,
+        elapsed: Duration,
+        decompress: bool,
+        output: Option<&str>,
+        secrets: &Secrets,
+    ) -> miette::Result<Option<String>> {
         let status_color = status_style(response.status());
 
         let output_arrows = status_color.apply_to("<<<");
@@ -83,26 +149,84 @@ impl super::Command {
         );
 
         let status = status_color.apply_to(response.status());
-        verbose!("{status} - {}", style(response.url()).underlined().blue());
+        let url = secrets.mask(response.url().as_str());
+        verbose!("{status} - {}", style(&url).underlined().blue());
         verbose!("{:?}", response.version());
 
-        print_headers(response.headers());
+        print_headers(response.headers(), &Secrets::default());
+
+        let envelope = (self.format == Format::Json).then(|| {
+            json!({
+                "url": url,
+                "status": response.status().as_u16(),
+                "version": format!("{:?}", response.version()),
+                "headers": headers_to_json(response.headers()),
+                "elapsed_ms": elapsed.as_millis(),
+            })
+        });
+
+        let encoding = response
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|e| e.to_str().ok())
+            .map(str::to_string);
+        let bytes = match response.bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!("{e}");
+                standard!(
+                    " {} The response body could not be read",
+                    style("x").red().bold()
+                );
+                return Ok(None);
+            }
+        };
+        let bytes = match encoding.filter(|_| decompress) {
+            Some(encoding) => decompress_body(&encoding, &bytes).unwrap_or_else(|| bytes.to_vec()),
+            None => bytes.to_vec(),
+        };
+        let body = String::from_utf8(bytes.clone()).ok();
+
+        if let Some(file) = output {
+            save_body(file, &bytes)?;
+        }
 
-        let body = response.text().await;
-        if body.is_err() {
-            error!("{}", body.unwrap_err());
+        if let Some(mut envelope) = envelope {
+            envelope["body"] = Self::body_to_json(body.as_deref(), output, &bytes);
+            if body.is_none() && output.is_none() {
+                envelope["body_encoding"] = json!("base64");
+            }
+            let envelope = if self.pretty_print {
+                to_string_pretty(&envelope)
+            } else {
+                to_string(&envelope)
+            };
+            print(envelope.unwrap());
+            return Ok(body);
+        }
+
+        if let Some(file) = output {
             standard!(
-                " {} The response body seems to not be printable",
-                style("x").red().bold()
+                "{status} - Saved {} bytes of the response body in {}",
+                bytes.len(),
+                style(file).yellow()
             );
-            return;
+            return Ok(body);
         }
-        let body = body.unwrap();
+
+        let Some(body) = body else {
+            standard!(
+                " {} The response body seems to not be printable, use {} to save it",
+                style("x").red().bold(),
+                style("--output").italic().yellow()
+            );
+            return Ok(None);
+        };
 
         if self.pretty_print {
-            if let Ok(json) = from_str::<serde_json::Value>(&body) {
+            if let Ok(json) = from_str::<Value>(&body) {
                 standard!("{}", to_string_pretty(&json).unwrap());
-                return;
+                return Ok(Some(body));
             }
         }
 
@@ -110,14 +234,76 @@ impl super::Command {
             standard!(below[Verbose] "{status}");
         }
         standard!("{body}");
+        Ok(Some(body))
+    }
+
+    /// Highlights the errors of a GraphQL response, as these are usually returned with a 200 status
+    pub(crate) fn print_graphql_errors(&self, body: Option<&str>) {
+        if self.format == Format::Json {
+            return;
+        }
+        let errors = body
+            .and_then(|body| from_str::<Value>(body).ok())
+            .and_then(|mut json| json.get_mut("errors").map(Value::take));
+        let Some(Value::Array(errors)) =
+            errors.filter(|e| e.as_array().is_some_and(|e| !e.is_empty()))
+        else {
+            return;
+        };
+
+        standard!(
+            "\n {} The GraphQL response contains {} error(s)",
+            style("x").red().bold(),
+            errors.len()
+        );
+        for error in &errors {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_string);
+            let mut details = Vec::new();
+            if let Some(path) = error.get("path").and_then(Value::as_array) {
+                let path = path
+                    .iter()
+                    .map(|p| p.as_str().map_or_else(|| p.to_string(), str::to_string))
+                    .collect::<Vec<String>>()
+                    .join(".");
+                details.push(format!("at {path}"));
+            }
+            if let Some(location) = error
+                .get("locations")
+                .and_then(|l| l.get(0))
+                .and_then(|l| Some((l.get("line")?, l.get("column")?)))
+            {
+                details.push(format!("line {}, column {}", location.0, location.1));
+            }
+            let details = if details.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", details.join(", "))
+            };
+            standard!("   - {}{}", style(message).red(), style(details).dim());
+        }
+    }
+
+    /// Body of the JSON envelope: JSON bodies are embedded, text ones are strings and binary ones
+    /// are encoded in base64. It's omitted if the body is empty or saved to a file.
+    fn body_to_json(body: Option<&str>, output: Option<&str>, bytes: &[u8]) -> Value {
+        if output.is_some() || bytes.is_empty() {
+            return Value::Null;
+        }
+        match body {
+            Some(text) => from_str(text).unwrap_or_else(|_| Value::String(text.to_string())),
+            None => Value::String(STANDARD.encode(bytes)),
+        }
     }
 
-    pub fn new_spinner(&self) -> ProgressBar {
+    pub fn new_spinner(filename: &str) -> ProgressBar {
         let mut bar = ProgressBar::new_spinner()
             .with_style(ProgressStyle::with_template(SPINNER_TEMPLATE).unwrap());
 
         bar = if_mode!([Standard] {
-            let prefix = format!("Launching request {}\n", style(&self.request).yellow().italic());
+            let prefix = format!("Launching request {}\n", style(filename).yellow().italic());
             bar.with_prefix(prefix)
         }, bar);
 
@@ -126,7 +312,7 @@ impl super::Command {
     }
 }
 
-pub(super) fn print_replacements(placeholder_values: &PlaceholderValues) {
+pub(super) fn print_replacements(placeholder_values: &PlaceholderValues, secrets: &Secrets) {
     verbose!("{} Placeholder replacement", style(">").bold().blue());
 
     let replacement_arrow = style(">").bold().green();
@@ -135,7 +321,7 @@ pub(super) fn print_replacements(placeholder_values: &PlaceholderValues) {
             "    {} {} {}",
             ph_replaced,
             &replacement_arrow,
-            ph_replacement,
+            secrets.mask(&ph_replacement),
         );
     }
     for ph_unresolved in placeholder_values.unresolved() {
@@ -144,18 +330,125 @@ pub(super) fn print_replacements(placeholder_values: &PlaceholderValues) {
     verbose!("");
 }
 
-fn print_headers(headers: &HeaderMap) {
+fn print_headers(headers: &HeaderMap, secrets: &Secrets) {
     // TODO create if_verbose! to wrap this loop and omit it
     for (header_key, header_value) in headers {
         verbose!(
             "  - {} : {}",
             header_key,
-            header_value.to_str().unwrap_or("<no ascii>")
+            secrets.mask(header_value.to_str().unwrap_or("<no ascii>"))
         );
     }
     verbose!("");
 }
 
+/// Values of the secret input params, masked when printed
+#[derive(Default)]
+pub(crate) struct Secrets(Vec<String>);
+
+impl Secrets {
+    #[cfg_attr(not(feature = "input_params"), allow(unused_variables))]
+    pub fn new(request: &Request, values: &PlaceholderValues) -> Self {
+        #[cfg(feature = "input_params")]
+        {
+            // along with their encodings, as they are encoded in the URLs
+            let mut secrets: Vec<String> = values
+                .resolved()
+                .filter(|(k, _)| request.input_params.get(*k).is_some_and(|ip| ip.secret))
+                .map(|(_, v)| v)
+                .filter(|v| !v.is_empty())
+                .flat_map(|v| {
+                    let mut encodings = url_encodings(&v);
+                    encodings.push(v);
+                    encodings
+                })
+                .collect();
+            secrets.sort();
+            secrets.dedup();
+            // the longest first, to not leave parts of them visible
+            secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+            Self(secrets)
+        }
+        #[cfg(not(feature = "input_params"))]
+        Self::default()
+    }
+
+    /// Replaces the secret values of the text with the mask
+    pub fn mask(&self, text: &str) -> String {
+        self.0
+            .iter()
+            .fold(text.to_string(), |text, secret| text.replace(secret, MASK))
+    }
+}
+
+/// Returns the ways the value can be encoded in a URL: in the path, in the query and in a query
+/// param added by the client
+#[cfg(feature = "input_params")]
+fn url_encodings(value: &str) -> Vec<String> {
+    let mut url = url::Url::parse("http://localhost").unwrap();
+    url.set_path(value);
+    url.set_query(Some(value));
+    vec![
+        url.path().trim_start_matches('/').to_string(),
+        url.query().unwrap_or_default().to_string(),
+        url::form_urlencoded::byte_serialize(value.as_bytes()).collect(),
+    ]
+}
+
+/// Decompresses the body with the algorithm of its `Content-Encoding`, printing the size before
+/// and after the decompression. Returns `None` if the encoding is unknown or it fails.
+fn decompress_body(encoding: &str, bytes: &[u8]) -> Option<Vec<u8>> {
+    match decompress(bytes, encoding)? {
+        Ok(decompressed) => {
+            verbose!(
+                "\n{} Body decompressed from {encoding}: {} -> {} bytes",
+                style("~").cyan(),
+                bytes.len(),
+                decompressed.len()
+            );
+            Some(decompressed)
+        }
+        Err(e) => {
+            error!("{e}");
+            standard!(
+                " {} The response body could not be decompressed from {encoding}",
+                style("x").red().bold()
+            );
+            None
+        }
+    }
+}
+
+/// Groups the headers by name, the headers with multiple values are represented as arrays
+fn headers_to_json(headers: &HeaderMap) -> Value {
+    let mut map = Map::new();
+    for name in headers.keys() {
+        let mut values = headers
+            .get_all(name)
+            .iter()
+            .map(|v| Value::String(String::from_utf8_lossy(v.as_bytes()).into_owned()))
+            .collect::<Vec<Value>>();
+        let value = if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        };
+        map.insert(name.to_string(), value);
+    }
+    Value::Object(map)
+}
+
+fn save_body(file: &str, bytes: &[u8]) -> miette::Result<()> {
+    if let Some(dir) = Path::new(file)
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+    {
+        create_dir_all(dir)
+            .map_err(|e| miette!("There was a problem creating the directory {dir:?}: {e}"))?;
+    }
+    write(file, bytes).map_err(|e| miette!("There was a problem writing the file {file}: {e}"))
+}
+
 fn method_style(method: &Method) -> Style {
     // postfix match would be quite nice here
     match *method {
@@ -0,0 +1,159 @@
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use console::style;
+use http::StatusCode;
+use log::debug;
+use miette::miette;
+use serde::Deserialize;
+
+use super::Format;
+use crate::commands::GlobalArgs;
+use crate::errors::ParsingError;
+use crate::terminal::{Mode, TERM_LOCK};
+use crate::util::input_to_string;
+
+/// Optional file of a suite directory defining the requests to run and their order
+const SUITE_MANIFEST: &str = "suite.toml";
+/// Files of the directory that can't be requests of a suite
+const NOT_REQUESTS: [&str; 2] = [SUITE_MANIFEST, "rede.env.toml"];
+
+#[derive(Deserialize)]
+struct Manifest {
+    requests: Vec<String>,
+}
+
+/// Result of the execution of one of the requests of the suite
+struct Execution {
+    filename: String,
+    outcome: Outcome,
+}
+
+enum Outcome {
+    /// Status of the response and the time it took to arrive
+    Response(StatusCode, Duration),
+    DryRun,
+    Error,
+}
+
+impl Outcome {
+    fn is_failure(&self) -> bool {
+        match self {
+            Outcome::Response(status, _) => status.is_client_error() || status.is_server_error(),
+            Outcome::DryRun => false,
+            Outcome::Error => true,
+        }
+    }
+}
+
+impl super::Command {
+    /// Executes sequentially all the requests of the given directory
+    pub(super) async fn run_suite(&self, gargs: &GlobalArgs) -> miette::Result<()> {
+        let requests = discover_requests(Path::new(&self.request))?;
+        debug!("Requests of the suite: {requests:?}");
+
+        let mut executions = Vec::with_capacity(requests.len());
+        for path in requests {
+            let filename = path.to_string_lossy().to_string();
+            let outcome = match self.execute(&filename, gargs).await {
+                Ok(Some((status, elapsed))) => Outcome::Response(status, elapsed),
+                Ok(None) => Outcome::DryRun,
+                Err(e) => {
+                    // the suite goes on, so the error is printed like the rest of the output
+                    TERM_LOCK
+                        .get()
+                        .unwrap()
+                        .eprint_above(Mode::Standard, format!("{e:?}"));
+                    Outcome::Error
+                }
+            };
+            executions.push(Execution { filename, outcome });
+        }
+
+        // the responses printed as JSON are consumed from stdout, so the summary can't be mixed
+        print_summary(&executions, self.format == Format::Json);
+
+        let failed = executions.iter().filter(|e| e.outcome.is_failure()).count();
+        if failed == 0 {
+            Ok(())
+        } else {
+            Err(miette!(
+                code = "failed suite",
+                url = "https://rede.sotoestevez.dev/reference_guide/errors.html#failed-suite",
+                "{failed} of {} requests of the suite failed",
+                executions.len()
+            ))
+        }
+    }
+}
+
+/// Lists the requests of the suite. If the directory has a manifest, its requests are used in
+/// the defined order, otherwise every TOML file of the directory is used in alphabetical order.
+fn discover_requests(dir: &Path) -> miette::Result<Vec<PathBuf>> {
+    let manifest = dir.join(SUITE_MANIFEST);
+    if manifest.is_file() {
+        let content = input_to_string(&manifest.to_string_lossy())?;
+        let manifest: Manifest = toml::from_str(&content)
+            .map_err(|e| ParsingError::parsing(content, rede_parser::Error::ParsingToml(e)))?;
+        return Ok(manifest.requests.iter().map(|r| dir.join(r)).collect());
+    }
+
+    let mut requests = read_dir(dir)
+        .map_err(|e| ParsingError::io(dir.to_string_lossy(), e))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_request(path))
+        .collect::<Vec<PathBuf>>();
+    requests.sort();
+    Ok(requests)
+}
+
+fn is_request(path: &Path) -> bool {
+    let is_toml = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let is_reserved = path
+        .file_name()
+        .is_some_and(|name| NOT_REQUESTS.iter().any(|n| name.eq_ignore_ascii_case(n)));
+    is_toml && !is_reserved
+}
+
+/// Prints the summary of the suite, to stderr if `to_stderr` is set
+fn print_summary(executions: &[Execution], to_stderr: bool) {
+    let terminal = TERM_LOCK.get().unwrap();
+    let print = |msg: String| {
+        if to_stderr {
+            terminal.eprint_above(Mode::Standard, msg);
+        } else {
+            terminal.print_above(Mode::Standard, msg);
+        }
+    };
+    print(format!("\n{} Suite summary", style(">").bold().blue()));
+    for execution in executions {
+        let (mark, result) = match &execution.outcome {
+            Outcome::Response(status, elapsed) => {
+                let mark = if execution.outcome.is_failure() {
+                    style("x").bold().red()
+                } else {
+                    style("✓").bold().green()
+                };
+                let elapsed = style(format!("({}ms)", elapsed.as_millis())).dim();
+                (mark, format!("{status} {elapsed}"))
+            }
+            Outcome::DryRun => (style("-").bold().yellow(), "dry run".to_string()),
+            Outcome::Error => (style("x").bold().red(), "error".to_string()),
+        };
+        print(format!(
+            "  {mark} {} {result}",
+            style(&execution.filename).yellow()
+        ));
+    }
+
+    let failed = executions.iter().filter(|e| e.outcome.is_failure()).count();
+    print(format!(
+        "\n{} passed, {} failed",
+        style(executions.len() - failed).green(),
+        style(failed).red()
+    ));
+}
@@ -1,4 +1,5 @@
 use console::style;
+use http::StatusCode;
 use miette::{Diagnostic, SourceSpan};
 use std::error::{Error as StdError, Error};
 use std::io::Error as IOError;
@@ -27,6 +28,22 @@ pub enum ParsingError {
         help("check if the file name is correct or you're in the correct path")
     )]
     IO { filename: String, source: IOError },
+    #[error("Failed to find the environment {}", style(name).yellow())]
+    #[diagnostic(
+        code("unknown environment"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#unknown-environment"),
+        help("define it in .rede/envs/{name}.toml or as a [{name}] table in rede.env.toml")
+    )]
+    UnknownEnvironment { name: String },
+    #[error("Failed to read the file {} included in the request: {message}", style(path).yellow())]
+    #[diagnostic(
+        code("invalid file"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-file"),
+        help(
+            "request file paths must be relative to the directory where `rede` is being executed"
+        )
+    )]
+    IncludedFile { path: String, message: String },
 }
 
 #[derive(Debug, Diagnostic, Error)]
@@ -61,6 +78,64 @@ pub enum RequestError<E: Error> {
         )
     )]
     IO { filename: String, source: IOError },
+    #[error("failed to compress the body of the request with {}", style(compression).yellow())]
+    #[diagnostic(
+        code = "failed compression",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#failed-compression"
+    )]
+    Compression {
+        compression: String,
+        source: IOError,
+    },
+    #[error("the cookie jar {} is not valid: {message}", style(filename).yellow())]
+    #[diagnostic(
+        code = "invalid cookie jar",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-cookie-jar",
+        help = "the files ending in .json must contain an array of cookies"
+    )]
+    CookieJar { filename: String, message: String },
+    #[error("the certificate {} could not be loaded: {message}", style(filename).yellow())]
+    #[diagnostic(
+        code = "invalid certificate",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-certificate",
+        help = "certificates must be PEM files with their PKCS#8 keys, or PKCS#12 archives with their password"
+    )]
+    Certificate { filename: String, message: String },
+    #[error("the certificate of {} could not be verified: {reason}", style(url).underlined().cyan())]
+    #[diagnostic(
+        code = "untrusted certificate",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#untrusted-certificate",
+        help = "trust its CA with --cacert, or skip the verification with --insecure for testing"
+    )]
+    UntrustedCertificate { url: String, reason: String },
+    #[error("the TLS handshake with {} failed: {reason}", style(url).underlined().cyan())]
+    #[diagnostic(
+        code = "failed tls handshake",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#failed-tls-handshake",
+        help = "check the minimum TLS version and if the server requires a client certificate"
+    )]
+    TlsHandshake { url: String, reason: String },
+    #[error("{} can't be required as minimum version", style(version).yellow())]
+    #[diagnostic(
+        code = "unsupported tls version",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#unsupported-tls-version",
+        help = "require TLS 1.2 instead, TLS 1.3 will still be used if the server supports it"
+    )]
+    TlsVersion { version: String },
+    #[error("failed to answer the digest challenge of the server")]
+    #[diagnostic(
+        code = "failed digest authentication",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#failed-digest-authentication",
+        help = "check that the WWW-Authenticate header of the server is a valid digest challenge"
+    )]
+    Digest { source: digest_auth::Error },
+    #[error("failed to obtain the OAuth2 access token: {message}")]
+    #[diagnostic(
+        code = "failed oauth2 authentication",
+        url = "https://rede.sotoestevez.dev/reference_guide/errors.html#failed-oauth2-authentication",
+        help = "check the token_url and the client credentials of the [auth.oauth2] table"
+    )]
+    OAuth2 { message: String },
     #[error(transparent)]
     #[diagnostic(
         code = "redirect",
@@ -86,6 +161,111 @@ pub enum RequestError<E: Error> {
     Unknown(E),
 }
 
+#[derive(Debug, Diagnostic, Error)]
+#[error("{} of the {total} asserts of the request failed", failures.len())]
+#[diagnostic(
+    code("failed asserts"),
+    url("https://rede.sotoestevez.dev/reference_guide/errors.html#failed-asserts")
+)]
+pub struct AssertsError {
+    pub total: usize,
+    #[related]
+    pub failures: Vec<FailedAssert>,
+}
+
+#[derive(Debug, Diagnostic, Error)]
+pub enum FailedAssert {
+    #[error("expected status {}, received {}", style(expected).green(), style(actual).red())]
+    #[diagnostic(code("assert: status"))]
+    Status {
+        expected: StatusCode,
+        actual: StatusCode,
+    },
+    #[error("expected header {} {expected}, received {}", style(name).yellow(), style(actual.as_deref().unwrap_or("nothing")).red())]
+    #[diagnostic(code("assert: header"))]
+    Header {
+        name: String,
+        expected: String,
+        actual: Option<String>,
+    },
+    #[error("expected {} to be \"{expected}\", received {}", style(path).yellow(), style(actual.as_deref().unwrap_or("nothing")).red())]
+    #[diagnostic(
+        code("assert: body"),
+        help("only JSON bodies and the JSONPath subset `$.key[0]['other']` are supported")
+    )]
+    Body {
+        path: String,
+        expected: String,
+        actual: Option<String>,
+    },
+    #[error("expected response in less than {}ms, received in {}ms", style(max).green(), style(actual).red())]
+    #[diagnostic(code("assert: response time"))]
+    ResponseTime { max: u128, actual: u128 },
+}
+
+#[derive(Debug, Diagnostic, Error)]
+pub enum ImportError {
+    #[error("Failed to split the curl command into arguments")]
+    #[diagnostic(
+        code("invalid curl command"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-curl-command"),
+        help("check that all the quotes of the command are closed")
+    )]
+    CurlSyntax { source: shell_words::ParseError },
+    #[error("Missing value for the curl option {}", style(option).yellow())]
+    #[diagnostic(
+        code("invalid curl command"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-curl-command")
+    )]
+    MissingValue { option: String },
+    #[error("The curl command doesn't have an URL")]
+    #[diagnostic(
+        code("invalid curl command"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-curl-command")
+    )]
+    MissingUrl,
+    #[error("Invalid header {}", style(header).yellow())]
+    #[diagnostic(
+        code("invalid curl command"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-curl-command"),
+        help("headers must follow the format `Name: value`")
+    )]
+    InvalidHeader { header: String },
+    #[error("Invalid method {}", style(method).yellow())]
+    #[diagnostic(
+        code("invalid curl command"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-curl-command")
+    )]
+    InvalidMethod { method: String },
+    #[error("Failed to parse the Postman file {}", style(filename).yellow())]
+    #[diagnostic(
+        code("invalid postman file"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-postman-file"),
+        help("only collections exported in the v2.1 format are supported")
+    )]
+    InvalidPostman {
+        filename: String,
+        source: serde_json::Error,
+    },
+    #[error("Failed to parse the OpenAPI document {}", style(filename).yellow())]
+    #[diagnostic(
+        code("invalid openapi document"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-openapi-document"),
+        help("the document must be a valid YAML or JSON file")
+    )]
+    InvalidOpenApi {
+        filename: String,
+        source: serde_yaml_ng::Error,
+    },
+    #[error("Unsupported OpenAPI version {}", style(version).yellow())]
+    #[diagnostic(
+        code("invalid openapi document"),
+        url("https://rede.sotoestevez.dev/reference_guide/errors.html#invalid-openapi-document"),
+        help("only OpenAPI 3 documents are supported, convert Swagger 2.0 documents first")
+    )]
+    UnsupportedOpenApi { version: String },
+}
+
 impl ParsingError {
     pub fn io<T: Into<String>>(filename: T, source: IOError) -> Self {
         Self::IO {
@@ -93,6 +273,9 @@ impl ParsingError {
             source,
         }
     }
+    pub fn unknown_environment<T: Into<String>>(name: T) -> Self {
+        Self::UnknownEnvironment { name: name.into() }
+    }
 
     pub fn parsing<T: Into<String>>(code: T, source: rede_parser::Error) -> Self {
         match source {
@@ -101,6 +284,9 @@ impl ParsingError {
                 code: code.into(),
                 span: e.span().map(SourceSpan::from),
             },
+            rede_parser::Error::ReadingFile { path, message } => {
+                ParsingError::IncludedFile { path, message }
+            }
         }
     }
 }
@@ -119,6 +305,26 @@ impl<E: Error> RequestError<E> {
             source,
         }
     }
+
+    pub fn cookie_jar<T: Into<String>>(filename: T, message: String) -> Self {
+        Self::CookieJar {
+            filename: filename.into(),
+            message,
+        }
+    }
+
+    pub fn certificate<T: Into<String>, M: Into<String>>(filename: T, message: M) -> Self {
+        Self::Certificate {
+            filename: filename.into(),
+            message: message.into(),
+        }
+    }
+
+    pub fn oauth2<T: Into<String>>(message: T) -> Self {
+        Self::OAuth2 {
+            message: message.into(),
+        }
+    }
 }
 
 impl From<reqwest::Error> for RequestError<reqwest::Error> {
@@ -128,7 +334,15 @@ impl From<reqwest::Error> for RequestError<reqwest::Error> {
         } else if value.is_timeout() {
             RequestError::Timeout(value)
         } else if value.is_connect() {
-            RequestError::FailedConnection(value)
+            let reason = root_cause(&value);
+            let url = value.url().map(ToString::to_string).unwrap_or_default();
+            if !is_tls_error(&value) {
+                RequestError::FailedConnection(value)
+            } else if is_untrusted_certificate(&reason) {
+                RequestError::UntrustedCertificate { url, reason }
+            } else {
+                RequestError::TlsHandshake { url, reason }
+            }
         } else if value.is_builder() {
             RequestError::Building(value)
         } else if value.is_request()
@@ -142,3 +356,39 @@ impl From<reqwest::Error> for RequestError<reqwest::Error> {
         }
     }
 }
+
+/// Checks if any of the causes of the error is a failure of the TLS backend, whatever the platform
+fn is_tls_error(error: &(dyn StdError + 'static)) -> bool {
+    let mut cause = Some(error);
+    while let Some(error) = cause {
+        if error.is::<native_tls::Error>() {
+            return true;
+        }
+        cause = error.source();
+    }
+    false
+}
+
+/// Checks if the TLS failure is due to the certificate of the server not being trusted. The TLS
+/// backend doesn't expose the kind of failure, so it's identified by the messages of OpenSSL on
+/// Linux, `Security.framework` on macOS and `SChannel` on Windows.
+fn is_untrusted_certificate(reason: &str) -> bool {
+    let reason = reason.to_lowercase();
+    [
+        "certificate verify failed",
+        "certificate is not trusted",
+        "invalid certificate chain",
+        "authority that is not trusted",
+    ]
+    .iter()
+    .any(|message| reason.contains(message))
+}
+
+/// Returns the message of the deepest cause of the error, the one explaining the failure
+pub fn root_cause(error: &dyn StdError) -> String {
+    let mut cause = error;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    cause.to_string()
+}
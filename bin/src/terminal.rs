@@ -56,6 +56,13 @@ impl Terminal {
         }
     }
 
+    #[inline]
+    pub fn eprint_above(&self, mode: Mode, msg: impl AsRef<str>) {
+        if self.mode >= mode {
+            eprint(msg);
+        }
+    }
+
     #[inline]
     pub fn print_between(&self, from: Mode, until: Mode, msg: impl AsRef<str>) {
         if self.mode >= from && self.mode < until {
@@ -75,3 +82,8 @@ pub(crate) enum Mode {
 pub fn print(msg: impl AsRef<str>) {
     println!("{}", msg.as_ref());
 }
+
+#[inline]
+pub fn eprint(msg: impl AsRef<str>) {
+    eprintln!("{}", msg.as_ref());
+}
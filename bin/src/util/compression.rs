@@ -0,0 +1,53 @@
+use std::io::{Read, Result, Write};
+use std::str::FromStr;
+
+use brotli::enc::BrotliEncoderParams;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use rede_schema::body::Compression;
+
+/// Size of the buffer used by the brotli decompressor
+const BROTLI_BUFFER: usize = 4096;
+
+/// Compresses the bytes with the given algorithm. The `deflate` encoding of HTTP is the zlib
+/// format, not the raw deflate one.
+pub fn compress(bytes: &[u8], compression: Compression) -> Result<Vec<u8>> {
+    match compression {
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+        Compression::Deflate => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes)?;
+            encoder.finish()
+        }
+        Compression::Brotli => {
+            let mut compressed = Vec::new();
+            brotli::BrotliCompress(
+                &mut &bytes[..],
+                &mut compressed,
+                &BrotliEncoderParams::default(),
+            )?;
+            Ok(compressed)
+        }
+        Compression::Zstd => zstd::encode_all(bytes, zstd::DEFAULT_COMPRESSION_LEVEL),
+    }
+}
+
+/// Decompresses the bytes encoded with the given `Content-Encoding`, returns `None` if the
+/// encoding is not supported
+pub fn decompress(bytes: &[u8], encoding: &str) -> Option<Result<Vec<u8>>> {
+    let compression = Compression::from_str(encoding.trim()).ok()?;
+    let mut decompressed = Vec::new();
+    let result = match compression {
+        Compression::Gzip => GzDecoder::new(bytes).read_to_end(&mut decompressed),
+        Compression::Deflate => ZlibDecoder::new(bytes).read_to_end(&mut decompressed),
+        Compression::Brotli => {
+            brotli::Decompressor::new(bytes, BROTLI_BUFFER).read_to_end(&mut decompressed)
+        }
+        Compression::Zstd => zstd::stream::copy_decode(bytes, &mut decompressed).map(|()| 0),
+    };
+    Some(result.map(|_| decompressed))
+}
@@ -1,3 +1,7 @@
+mod compression;
 mod read;
+mod write;
 
+pub use compression::{compress, decompress};
 pub use read::input_to_string;
+pub use write::{request_to_toml, title_case};
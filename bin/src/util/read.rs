@@ -18,7 +18,7 @@ pub fn input_to_string(source: &str) -> Result<String> {
     Ok(buffer)
 }
 
-fn open_file_or_stdin(filename: &str) -> Result<(Cow<str>, Box<dyn BufRead>)> {
+fn open_file_or_stdin(filename: &str) -> Result<(Cow<'_, str>, Box<dyn BufRead>)> {
     if filename == STDIN_ARG {
         debug!("Reading request from [STDIN]");
         let input = io::stdin();
@@ -37,10 +37,10 @@ fn open_file_or_stdin(filename: &str) -> Result<(Cow<str>, Box<dyn BufRead>)> {
 }
 
 #[inline]
-fn add_extension(filename: &str) -> Cow<str> {
+fn add_extension(filename: &str) -> Cow<'_, str> {
     if Path::new(filename)
         .extension()
-        .map_or(false, |ext| ext.eq_ignore_ascii_case("toml"))
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
     {
         filename.into()
     } else {
@@ -0,0 +1,435 @@
+use std::collections::BTreeMap;
+
+use http::{HeaderMap, Version};
+use mime::Mime;
+use rede_schema::auth::{ClientAuth, Grant};
+use rede_schema::body::FormDataValue;
+use rede_schema::tls::ClientCert;
+use rede_schema::{Auth, Body, Request, Tls};
+#[cfg(feature = "input_params")]
+use rede_schema::{InputParam, InputType};
+use serde::Serialize;
+
+/// Model of a request file, it borrows the contents of a [`Request`] to serialize them in the
+/// same layout expected by the parser.
+#[derive(Serialize)]
+struct RequestFile<'r> {
+    http: Http<'r>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: &'r BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, Values<'r>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    query_params: BTreeMap<&'r str, Values<'r>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    cookies: &'r BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<BodyFile<'r>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auth: Option<AuthFile<'r>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tls: Option<TlsFile<'r>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    variables: &'r BTreeMap<String, String>,
+    #[cfg(feature = "input_params")]
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    input_params: BTreeMap<&'r str, InputParamFile<'r>>,
+}
+
+#[derive(Serialize)]
+struct Http<'r> {
+    method: &'r str,
+    url: &'r str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compression: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decompress: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Values<'r> {
+    Single(&'r str),
+    Multiple(Vec<&'r str>),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum BodyFile<'r> {
+    Raw(&'r str),
+    Json(&'r str),
+    #[serde(rename = "json")]
+    JsonTable(&'r serde_json::Value),
+    Xml(&'r str),
+    Gzip(&'r str),
+    Zip(&'r str),
+    Gif(&'r str),
+    Pdf(&'r str),
+    Png(&'r str),
+    Jpeg(&'r str),
+    Binary(&'r str),
+    FormData(BTreeMap<&'r str, FormDataFile<'r>>),
+    #[serde(rename = "x-www-form-urlencoded")]
+    XFormUrlEncoded(&'r BTreeMap<String, String>),
+    #[serde(rename = "graphql")]
+    GraphQL {
+        query: &'r str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        variables: Option<&'r serde_json::Value>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        operation_name: Option<&'r str>,
+    },
+}
+
+#[derive(Serialize)]
+struct FormDataFile<'r> {
+    #[serde(flatten)]
+    value: FormDataValueFile<'r>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filename: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_type: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    headers: BTreeMap<String, Values<'r>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FormDataValueFile<'r> {
+    Text(&'r str),
+    File(&'r str),
+}
+
+#[derive(Serialize)]
+struct TlsFile<'r> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ca_cert: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cert: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pkcs12: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    password: Option<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_version: Option<&'static str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    insecure: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum AuthFile<'r> {
+    Basic {
+        username: &'r str,
+        password: &'r str,
+    },
+    Bearer(&'r str),
+    Digest {
+        username: &'r str,
+        password: &'r str,
+    },
+    ApiKey {
+        key: &'r str,
+        value: &'r str,
+        #[serde(rename = "in")]
+        location: String,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        grant_type: String,
+        token_url: &'r str,
+        client_id: &'r str,
+        #[serde(skip_serializing_if = "str::is_empty")]
+        client_secret: &'r str,
+        #[serde(skip_serializing_if = "str::is_empty")]
+        scope: &'r str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        client_auth: Option<&'r str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        username: Option<&'r str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        password: Option<&'r str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        refresh_token: Option<&'r str>,
+    },
+}
+
+#[cfg(feature = "input_params")]
+#[derive(Serialize)]
+struct InputParamFile<'r> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<&'r str>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    secret: bool,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    kind: Option<InputTypeFile<'r>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    choices: &'r [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<&'r str>,
+}
+
+#[cfg(feature = "input_params")]
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum InputTypeFile<'r> {
+    Int,
+    Float,
+    Bool,
+    Url,
+    Email,
+    Regex(&'r str),
+}
+
+/// Generates the content of a request file representing the given request. Only the parts of
+/// the request used to build the HTTP request are written, asserts and captures are omitted.
+pub fn request_to_toml(request: &Request) -> Result<String, toml::ser::Error> {
+    let file = RequestFile {
+        http: Http {
+            method: request.method.as_str(),
+            url: &request.url,
+            version: (request.http_version != Version::HTTP_11)
+                .then(|| format!("{:?}", request.http_version)),
+            compression: request.compression.map(|c| c.encoding()),
+            decompress: request.decompress,
+        },
+        metadata: &request.metadata,
+        headers: headers(&request.headers, &request.templated_headers),
+        query_params: group(
+            request
+                .query_params
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        ),
+        cookies: &request.cookies,
+        body: body(&request.body),
+        auth: auth(&request.auth),
+        tls: tls(&request.tls),
+        variables: &request.variables,
+        #[cfg(feature = "input_params")]
+        input_params: request
+            .input_params
+            .iter()
+            .map(|(k, v)| (k.as_str(), input_param(v)))
+            .collect(),
+    };
+    toml::to_string(&file)
+}
+
+#[cfg(feature = "input_params")]
+fn input_param(input_param: &InputParam) -> InputParamFile<'_> {
+    let kind = match &input_param.kind {
+        InputType::String => None,
+        InputType::Int => Some(InputTypeFile::Int),
+        InputType::Float => Some(InputTypeFile::Float),
+        InputType::Bool => Some(InputTypeFile::Bool),
+        InputType::Url => Some(InputTypeFile::Url),
+        InputType::Email => Some(InputTypeFile::Email),
+        InputType::Regex(pattern) => Some(InputTypeFile::Regex(pattern)),
+    };
+    InputParamFile {
+        hint: input_param.hint.as_deref(),
+        secret: input_param.secret,
+        kind,
+        choices: &input_param.choices,
+        default: input_param.default.as_deref(),
+    }
+}
+
+/// Headers with placeholders in their names are written as they are
+fn headers<'r>(
+    headers: &'r HeaderMap,
+    templated: &'r [(String, String)],
+) -> BTreeMap<String, Values<'r>> {
+    let mut map: BTreeMap<String, Values> = group(
+        headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.to_str().unwrap_or_default())),
+    )
+    .into_iter()
+    .map(|(k, v)| (title_case(k), v))
+    .collect();
+    map.extend(
+        group(templated.iter().map(|(k, v)| (k.as_str(), v.as_str())))
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v)),
+    );
+    map
+}
+
+/// Groups the values of repeated keys into arrays
+fn group<'r>(pairs: impl Iterator<Item = (&'r str, &'r str)>) -> BTreeMap<&'r str, Values<'r>> {
+    let mut map: BTreeMap<&str, Values> = BTreeMap::new();
+    for (key, value) in pairs {
+        let entry = match map.remove(key) {
+            None => Values::Single(value),
+            Some(Values::Single(first)) => Values::Multiple(vec![first, value]),
+            Some(Values::Multiple(mut values)) => {
+                values.push(value);
+                Values::Multiple(values)
+            }
+        };
+        map.insert(key, entry);
+    }
+    map
+}
+
+/// Converts header names like `content-type` into `Content-Type`
+pub fn title_case(name: &str) -> String {
+    name.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+fn body(body: &Body) -> Option<BodyFile<'_>> {
+    match body {
+        Body::None => None,
+        Body::Raw { content, mime } => Some(raw_body(content, mime)),
+        Body::Json(value) => Some(BodyFile::JsonTable(value)),
+        Body::Binary { path, mime } => Some(binary_body(path, mime)),
+        Body::FormData(form) => Some(BodyFile::FormData(
+            form.iter()
+                .map(|(k, v)| {
+                    let value = match &v.value {
+                        FormDataValue::Text(text) => FormDataValueFile::Text(text),
+                        FormDataValue::File(path) => FormDataValueFile::File(path),
+                    };
+                    let part = FormDataFile {
+                        value,
+                        filename: v.filename.as_deref(),
+                        content_type: v.content_type.as_ref().map(ToString::to_string),
+                        headers: headers(&v.headers, &[]),
+                    };
+                    (k.as_str(), part)
+                })
+                .collect(),
+        )),
+        Body::XFormUrlEncoded(form) => Some(BodyFile::XFormUrlEncoded(form)),
+        Body::GraphQL(graphql) => Some(BodyFile::GraphQL {
+            query: &graphql.query,
+            variables: graphql.variables.as_ref(),
+            operation_name: graphql.operation_name.as_deref(),
+        }),
+    }
+}
+
+fn tls(tls: &Tls) -> Option<TlsFile<'_>> {
+    if *tls == Tls::default() {
+        return None;
+    }
+    let (cert, key, pkcs12, password) = match &tls.client_cert {
+        Some(ClientCert::Pem { cert, key }) => (Some(cert), Some(key), None, None),
+        Some(ClientCert::Pkcs12 { path, password }) => (None, None, Some(path), Some(password)),
+        None => (None, None, None, None),
+    };
+    Some(TlsFile {
+        ca_cert: tls.ca_cert.as_deref(),
+        cert: cert.map(String::as_str),
+        key: key.map(String::as_str),
+        pkcs12: pkcs12.map(String::as_str),
+        password: password.map(String::as_str).filter(|p| !p.is_empty()),
+        min_version: tls.min_version.map(|v| v.number()),
+        insecure: tls.insecure,
+    })
+}
+
+fn auth(auth: &Auth) -> Option<AuthFile<'_>> {
+    match auth {
+        Auth::None => None,
+        Auth::Basic { username, password } => Some(AuthFile::Basic { username, password }),
+        Auth::Bearer { token } => Some(AuthFile::Bearer(token)),
+        Auth::Digest { username, password } => Some(AuthFile::Digest { username, password }),
+        Auth::ApiKey {
+            key,
+            value,
+            location,
+        } => Some(AuthFile::ApiKey {
+            key,
+            value,
+            location: location.to_string(),
+        }),
+        Auth::OAuth2(oauth2) => {
+            let (username, password, refresh_token) = match &oauth2.grant {
+                Grant::ClientCredentials => (None, None, None),
+                Grant::Password { username, password } => {
+                    (Some(username.as_str()), Some(password.as_str()), None)
+                }
+                Grant::RefreshToken { refresh_token } => (None, None, Some(refresh_token.as_str())),
+            };
+            Some(AuthFile::OAuth2 {
+                grant_type: oauth2.grant.to_string(),
+                token_url: &oauth2.token_url,
+                client_id: &oauth2.client_id,
+                client_secret: &oauth2.client_secret,
+                scope: &oauth2.scope,
+                client_auth: (oauth2.client_auth == ClientAuth::Basic).then_some("basic"),
+                username,
+                password,
+                refresh_token,
+            })
+        }
+    }
+}
+
+fn raw_body<'r>(content: &'r str, mime: &Mime) -> BodyFile<'r> {
+    match mime.essence_str() {
+        "application/json" => BodyFile::Json(content),
+        "text/xml" => BodyFile::Xml(content),
+        _ => BodyFile::Raw(content),
+    }
+}
+
+fn binary_body<'r>(path: &'r str, mime: &Mime) -> BodyFile<'r> {
+    match mime.essence_str() {
+        "application/gzip" => BodyFile::Gzip(path),
+        "application/zip" => BodyFile::Zip(path),
+        "image/gif" => BodyFile::Gif(path),
+        "application/pdf" => BodyFile::Pdf(path),
+        "image/png" => BodyFile::Png(path),
+        "image/jpeg" => BodyFile::Jpeg(path),
+        _ => BodyFile::Binary(path),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn templated_headers_round_trip() {
+        let mut request = Request {
+            url: "http://localhost:8080".to_string(),
+            templated_headers: vec![
+                ("X-{{tenant}}-Tag".to_string(), "a".to_string()),
+                ("X-{{tenant}}-Tag".to_string(), "b".to_string()),
+                ("{{tenant}}-Id".to_string(), "{{id}}".to_string()),
+            ],
+            ..Default::default()
+        };
+        request
+            .headers
+            .insert("accept", HeaderValue::from_static("application/json"));
+
+        let toml = request_to_toml(&request).unwrap();
+        let parsed = rede_parser::parse_request(&toml).unwrap();
+
+        assert_eq!(parsed.headers, request.headers);
+        assert_eq!(parsed.templated_headers, {
+            let mut headers = request.templated_headers.clone();
+            headers.sort();
+            headers
+        });
+    }
+}
@@ -0,0 +1,142 @@
+//! Helpers shared by the tests running requests against stub servers. The stubs are plain HTTP/1.1
+//! servers in a background thread, answering each request with the response of a handler.
+#![allow(dead_code)] // each test only uses some of the helpers
+
+use std::fs::create_dir_all;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::thread;
+
+/// Request received by a stub
+pub struct StubRequest {
+    /// Request line, like `GET /path HTTP/1.1`
+    pub line: String,
+    /// Headers with their names in lowercase
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Response sent by a stub, the `content-length` and `connection: close` headers are added
+pub struct StubResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl StubRequest {
+    /// Returns the first value of the header
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8(self.body.clone()).unwrap()
+    }
+}
+
+impl StubResponse {
+    pub fn new(status: u16, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: vec![("content-type".to_string(), content_type.to_string())],
+            body: body.into(),
+        }
+    }
+
+    pub fn text(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, "text/plain", body)
+    }
+
+    pub fn json(body: impl Into<Vec<u8>>) -> Self {
+        Self::new(200, "application/json", body)
+    }
+
+    #[must_use]
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Starts a stub answering the requests with the handler, returns its URL
+pub fn stub(handler: impl Fn(StubRequest) -> StubResponse + Send + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            if let Some(request) = read_request(&mut stream) {
+                write_response(&mut stream, &handler(request));
+            }
+        }
+    });
+    url
+}
+
+/// Reads the request from the stream, returns `None` if the connection is closed before
+/// the end of the headers
+pub fn read_request(stream: impl Read) -> Option<StubRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).ok().filter(|read| *read > 0)?;
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .ok()
+            .filter(|read| *read > 0)?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+    let content_length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .map_or(0, |(_, value)| value.parse().unwrap());
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+
+    Some(StubRequest {
+        line: line.trim_end().to_string(),
+        headers,
+        body,
+    })
+}
+
+pub fn write_response(mut stream: impl Write, response: &StubResponse) {
+    let mut head = format!("HTTP/1.1 {} Stub\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!(
+        "content-length: {}\r\nconnection: close\r\n\r\n",
+        response.body.len()
+    ));
+    let _ = stream
+        .write_all(head.as_bytes())
+        .and_then(|()| stream.write_all(&response.body));
+}
+
+/// Creates a clean temporary directory for the test, like `rede_<group>_<name>_<pid>`
+pub fn workspace(group: &str, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rede_{group}_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::io::{Read, Write};
+
+use assert_cmd::Command;
+use common::{StubRequest, StubResponse};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use predicates::prelude::predicate::str::contains;
+use predicates::prelude::PredicateBooleanExt;
+
+// Tests of the body compression against a stub server. The stub decompresses the body of the
+// request and answers with it, the Content-Encoding of the request and the encodings accepted,
+// compressing the response with gzip if it's accepted.
+
+fn stub() -> String {
+    common::stub(|request: StubRequest| {
+        let encoding = request.header("content-encoding").unwrap_or("none");
+        let accepted = request.header("accept-encoding").unwrap_or("none");
+        let body = match encoding {
+            "gzip" => {
+                let mut decompressed = String::new();
+                GzDecoder::new(&request.body[..])
+                    .read_to_string(&mut decompressed)
+                    .unwrap();
+                decompressed
+            }
+            "zstd" => String::from_utf8(zstd::decode_all(&request.body[..]).unwrap()).unwrap(),
+            _ => request.text(),
+        };
+
+        let response =
+            format!("encoding: {encoding}\naccepted: {accepted}\nbody: {body}").into_bytes();
+        if accepted.contains("gzip") {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&response).unwrap();
+            StubResponse::text(encoder.finish().unwrap()).with_header("content-encoding", "gzip")
+        } else {
+            StubResponse::text(response)
+        }
+    })
+}
+
+fn run(url: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--verbose", "run"])
+        .args(["--param", &format!("url={url}")])
+        .args(args)
+        .arg("tests/inputs/compression")
+        .assert()
+}
+
+#[test]
+fn compressed_request() {
+    let url = stub();
+    run(&url, &[]).success().stdout(
+        contains("Body compressed with gzip: 42 -> ")
+            .and(contains("encoding: gzip\n"))
+            .and(contains("body: rede compresses the bodies of the requests")),
+    );
+    run(&url, &["--compress=zstd"])
+        .success()
+        .stdout(contains("Body compressed with zstd: 42 -> ").and(contains("encoding: zstd\n")));
+}
+
+#[test]
+fn decompressed_response() {
+    let url = stub();
+    run(&url, &[]).success().stdout(
+        contains("accepted: gzip, deflate, br, zstd\n")
+            .and(contains("Body decompressed from gzip: "))
+            .and(contains(" -> 97 bytes")),
+    );
+    run(&url, &["--decompress=false"])
+        .success()
+        .stdout(contains("accepted: none\n").and(contains("Body decompressed").not()));
+}
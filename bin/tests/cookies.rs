@@ -0,0 +1,87 @@
+mod common;
+
+use std::fs::{read_to_string, write};
+
+use assert_cmd::Command;
+use common::{workspace, StubRequest, StubResponse};
+use predicates::prelude::predicate::str::contains;
+use predicates::prelude::PredicateBooleanExt;
+
+// Tests of the cookie jar against a stub server. The stub answers with the cookies received and
+// sets a session cookie in every response.
+
+fn stub() -> String {
+    common::stub(|request: StubRequest| {
+        let cookie = request.header("cookie").unwrap_or("none");
+        StubResponse::text(format!("cookie: {cookie}"))
+            .with_header("set-cookie", "session=abc; Path=/; HttpOnly")
+    })
+}
+
+fn run(url: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--verbose", "run"])
+        .args(["--param", &format!("url={url}")])
+        .args(args)
+        .arg("tests/inputs/cookies")
+        .assert()
+}
+
+#[test]
+fn cookies_table() {
+    let url = stub();
+    run(&url, &[])
+        .success()
+        .stdout(contains("Cookies theme=dark\n").and(contains("cookie: theme=dark")));
+    run(&url, &["--param", "theme=light"])
+        .success()
+        .stdout(contains("cookie: theme=light"));
+}
+
+#[test]
+fn netscape_cookie_jar() {
+    let url = stub();
+    let jar = workspace("cookies", "netscape").join("cookies.txt");
+    let jar = jar.to_str().unwrap();
+
+    run(&url, &["--cookie-jar", jar])
+        .success()
+        .stdout(contains("cookie: theme=dark"));
+    let content = read_to_string(jar).unwrap();
+    assert!(content.starts_with("# Netscape HTTP Cookie File\n"));
+    assert!(content.contains("#HttpOnly_127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc\n"));
+
+    run(&url, &["--cookie-jar", jar])
+        .success()
+        .stdout(contains("cookie: session=abc; theme=dark"));
+}
+
+#[test]
+fn json_cookie_jar() {
+    let url = stub();
+    let jar = workspace("cookies", "json").join("cookies.json");
+    write(
+        &jar,
+        r#"[{"name": "theme", "value": "stored", "domain": "127.0.0.1"}, {"name": "lang", "value": "en", "domain": "127.0.0.1"}]"#,
+    )
+    .unwrap();
+    let jar = jar.to_str().unwrap();
+
+    run(&url, &["--cookie-jar", jar])
+        .success()
+        .stdout(contains("cookie: lang=en; theme=dark").and(contains("theme=stored").not()));
+    let content = read_to_string(jar).unwrap();
+    assert!(content.contains(r#""name": "session","#));
+    assert!(content.contains(r#""name": "lang","#));
+}
+
+#[test]
+fn invalid_cookie_jar() {
+    let jar = workspace("cookies", "invalid").join("cookies.json");
+    write(&jar, "session=abc").unwrap();
+
+    run("http://localhost", &["--cookie-jar", jar.to_str().unwrap()])
+        .failure()
+        .stderr(contains("invalid cookie jar"));
+}
@@ -0,0 +1,166 @@
+use assert_cmd::Command;
+use predicates::boolean::PredicateBooleanExt;
+use predicates::prelude::predicate::str::{contains, starts_with};
+
+#[test]
+fn export_curl() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("tests/inputs/headers")
+        .assert()
+        .success()
+        .stdout(
+            starts_with("curl http://localhost:8080/api/request \\\n")
+                .and(contains("-H 'Accept: application/json'"))
+                .and(contains("-H 'User-Agent: rede'")),
+        );
+}
+
+#[test]
+fn export_curl_form_data() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("--format=curl")
+        .arg("tests/inputs/body_form_data")
+        .assert()
+        .success()
+        .stdout(
+            starts_with("curl -X POST http://localhost:8080/api/multipart")
+                .and(contains("-F 'binary=@./tests/assets/bin_file_for_test'"))
+                .and(contains("--form-string 'raw=agarimo'")),
+        );
+}
+
+#[test]
+fn export_form_data_parts() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("--format=curl")
+        .arg("tests/inputs/body_form_data_parts")
+        .assert()
+        .success()
+        .stdout(
+            contains("-F 'moon=@./tests/assets/moon.png' \\\n")
+                .and(contains(
+                    "-F 'report=@./tests/assets/bin_file_for_test;type=text/plain;filename=report.txt'",
+                ))
+                .and(contains(
+                    r#"-F 'metadata="{\"name\": \"moon\"}";type=application/json;headers="X-Part: metadata"'"#,
+                )),
+        );
+
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("--format=http")
+        .arg("tests/inputs/body_form_data_parts")
+        .assert()
+        .success()
+        .stdout(
+            contains(
+                "Content-Disposition: form-data; name=\"moon\"; filename=\"moon.png\"\r\n\
+                Content-Type: image/png\r\n\r\n< ./tests/assets/moon.png",
+            )
+            .and(contains(
+                "Content-Disposition: form-data; name=\"report\"; filename=\"report.txt\"\r\n\
+                Content-Type: text/plain\r\n",
+            ))
+            .and(contains(
+                "name=\"metadata\"\r\nContent-Type: application/json\r\nX-Part: metadata\r\n",
+            )),
+        );
+}
+
+#[test]
+fn export_httpie() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("--format=httpie")
+        .arg("tests/inputs/query_params")
+        .assert()
+        .success()
+        .stdout(contains(
+            "http GET 'http://localhost:8080/api/request?name=Robert&name=Edward&page=1&size=10'",
+        ));
+}
+
+#[test]
+fn export_http() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("--format=http")
+        .arg("tests/inputs/body_form_url_encoded")
+        .assert()
+        .success()
+        .stdout(starts_with(
+            "POST /api/urlencoded HTTP/1.1\r\nHost: localhost:8080\r\n\
+            Content-Type: application/x-www-form-urlencoded\r\nContent-Length: 26\r\n\r\n\
+            anime=Evangelion&rating=10",
+        ));
+}
+
+#[test]
+fn export_rendered_placeholders() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .env("endpoint", "api/exported")
+        .arg("--no-color")
+        .arg("export")
+        .arg("--no-input")
+        .arg("tests/inputs/replace_variables")
+        .assert()
+        .success()
+        .stdout(contains("/api/exported"));
+}
+
+#[test]
+fn export_unresolved_placeholders() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("--no-input")
+        .arg("tests/inputs/unresolved_placeholders")
+        .assert()
+        .failure()
+        .stderr(contains("unresolved placeholders"));
+}
+
+#[test]
+fn export_auth() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .arg("tests/inputs/auth_bearer")
+        .assert()
+        .success()
+        .stdout(contains("-H 'Authorization: Bearer rede-token'"));
+}
+
+#[test]
+fn export_tls() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("export")
+        .args(["--param", "url=https://localhost", "--param", "certs=certs"])
+        .arg("tests/inputs/tls_table")
+        .assert()
+        .success()
+        .stdout(
+            contains("--cacert certs/ca.pem \\\n")
+                .and(contains("--cert certs/client.pem --key certs/client.key")),
+        );
+}
@@ -0,0 +1,54 @@
+use assert_cmd::Command;
+use predicates::boolean::PredicateBooleanExt;
+use predicates::prelude::predicate::str::contains;
+
+#[test]
+fn openapi_operations() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("--dry-run")
+        .arg("--verbose")
+        .arg("generate")
+        .arg("openapi")
+        .arg("tests/openapi/petstore.yaml")
+        .assert()
+        .success()
+        .stdout(
+            contains("Generated pet_store/get_health.toml")
+                .and(contains("Generated pet_store/pets/list_pets.toml"))
+                .and(contains("Generated pet_store/pets/create_pet.toml"))
+                .and(contains("Generated pet_store/pets/show_pet_by_id.toml"))
+                .and(contains(r#"base_url = "http://localhost:8080/api""#))
+                .and(contains(r#"url = "{{base_url}}/pets/{{petId}}""#))
+                .and(contains(r#"hint = "Identifier of the pet""#))
+                .and(contains(r#"limit = "20""#))
+                .and(contains(r#"status = "{{status}}""#))
+                .and(contains(r#"choices = ["available", "sold"]"#))
+                .and(contains("cursor =").not())
+                .and(contains(
+                    "Omitting the optional query param cursor of get /pets",
+                ))
+                .and(contains(r#"ownerId = "7""#))
+                .and(contains("input_params.ownerId").not())
+                .and(contains(
+                    "[input_params.X-Trace]\nhint = \"Trace with a digit\"\n\n",
+                ))
+                .and(contains(r#"X-Request-Id = "rede-1""#))
+                .and(contains(r#""name": "Rex""#)),
+        );
+}
+
+#[test]
+fn openapi_unsupported_version() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("--dry-run")
+        .arg("generate")
+        .arg("openapi")
+        .arg("tests/postman/environment.json")
+        .assert()
+        .failure()
+        .stderr(contains("invalid openapi document"));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use assert_cmd::Command;
+use common::{StubRequest, StubResponse};
+use predicates::prelude::predicate::str::contains;
+
+// Tests of the GraphQL bodies against a stub server. The stub answers with the received body
+// as the `data` of the response, and with an error if the query asks for an unknown field.
+
+fn stub() -> String {
+    common::stub(|request: StubRequest| {
+        let body = request.text();
+        StubResponse::json(if body.contains("unknown") {
+            r#"{"data":null,"errors":[{"message":"Cannot query field \"unknown\" on type \"Pet\".","locations":[{"line":1,"column":17}],"path":["pet",0]}]}"#.to_string()
+        } else {
+            format!(r#"{{"data":{body}}}"#)
+        })
+    })
+}
+
+fn run(url: &str, field: &str) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "run", "--pretty-print=false"])
+        .args(["--param", &format!("url={url}")])
+        .args(["--param", &format!("field={field}")])
+        .arg("tests/inputs/graphql")
+        .assert()
+}
+
+#[test]
+fn graphql_envelope() {
+    let url = stub();
+    run(&url, "name").success().stdout(contains(
+        r#"{"data":{"query":"query Pet($id: ID!) { pet(id: $id) { name } }","operationName":"Pet","variables":{"id":1}}}"#,
+    ));
+}
+
+#[test]
+fn graphql_errors() {
+    let url = stub();
+    run(&url, "unknown")
+        .success()
+        .stdout(contains("The GraphQL response contains 1 error(s)"))
+        .stdout(contains(
+            r#"- Cannot query field "unknown" on type "Pet". (at pet.0, line 1, column 17)"#,
+        ));
+}
@@ -0,0 +1,230 @@
+use assert_cmd::Command;
+use predicates::boolean::PredicateBooleanExt;
+use predicates::prelude::predicate::str::contains;
+
+#[test]
+fn curl_get() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl -sL 'https://example.org/api?page=2&tag=a&tag=b' -H 'Accept: text/html'")
+        .assert()
+        .success()
+        .stdout(
+            contains(r#"url = "https://example.org/api""#)
+                .and(contains(r#"method = "GET""#))
+                .and(contains(r#"Accept = "text/html""#))
+                .and(contains(r#"page = "2""#))
+                .and(contains(r#"tag = ["a", "b"]"#)),
+        );
+}
+
+#[test]
+fn curl_post_json() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .write_stdin(
+            "curl -XPOST http://localhost:8080/users \\\n  -u admin:secret \\\n  -d '{\"name\": \"rede\"}'",
+        )
+        .assert()
+        .success()
+        .stdout(
+            contains(r#"method = "POST""#)
+                .and(contains("[auth.basic]"))
+                .and(contains(r#"username = "admin""#))
+                .and(contains(r#"password = "secret""#))
+                .and(contains("[body]"))
+                .and(contains(r#""name": "rede""#)),
+        );
+}
+
+#[test]
+fn curl_form() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl localhost:8080 -d user=rede -d 'pass=a%20b' -F ignored=x")
+        .assert()
+        .success()
+        .stdout(
+            contains(r#"method = "POST""#)
+                .and(contains("[body.form_data.ignored]"))
+                .and(contains(r#"text = "x""#)),
+        );
+}
+
+#[test]
+fn curl_form_part_modifiers() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl localhost:8080 -F 'avatar=@me.png;type=image/png;filename=avatar.png'")
+        .assert()
+        .success()
+        .stdout(
+            contains("[body.form_data.avatar]")
+                .and(contains(r#"file = "me.png""#))
+                .and(contains(r#"filename = "avatar.png""#))
+                .and(contains(r#"content_type = "image/png""#)),
+        );
+}
+
+#[test]
+fn curl_tls() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl https://localhost -k --cacert ca.pem --cert-type P12 --cert 'client.p12:s3cr3t' --tlsv1.2")
+        .assert()
+        .success()
+        .stdout(
+            contains("[tls]")
+                .and(contains(r#"ca_cert = "ca.pem""#))
+                .and(contains(r#"pkcs12 = "client.p12""#))
+                .and(contains(r#"password = "s3cr3t""#))
+                .and(contains(r#"min_version = "1.2""#))
+                .and(contains("insecure = true")),
+        );
+}
+
+#[test]
+fn curl_url_encoded() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl localhost:8080 --data user=rede --data-urlencode 'pass=a b'")
+        .assert()
+        .success()
+        .stdout(
+            contains("[body.x-www-form-urlencoded]")
+                .and(contains(r#"user = "rede""#))
+                .and(contains(r#"pass = "a b""#)),
+        );
+}
+
+#[test]
+fn curl_bundled_flags() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl -Gd 'q=1' http://example.com/search")
+        .assert()
+        .success()
+        .stdout(
+            contains(r#"url = "http://example.com/search""#)
+                .and(contains(r#"method = "GET""#))
+                .and(contains(r#"q = "1""#)),
+        );
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl -sSLXPUT -0d a=b http://example.com/item")
+        .assert()
+        .success()
+        .stdout(
+            contains(r#"url = "http://example.com/item""#)
+                .and(contains(r#"method = "PUT""#))
+                .and(contains(r#"version = "HTTP/1.0""#))
+                .and(contains(r#"a = "b""#)),
+        );
+}
+
+#[test]
+fn curl_round_trip() {
+    let output = Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg(
+            "curl -X PUT http://localhost:8080/item -H 'Content-Type: text/plain' --data-raw hello",
+        )
+        .output()
+        .unwrap();
+
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("--dry-run")
+        .arg("--verbose")
+        .arg("run")
+        .arg("-")
+        .write_stdin(output.stdout)
+        .assert()
+        .success()
+        .stdout(contains("PUT http://localhost:8080/item").and(contains("hello")));
+}
+
+#[test]
+fn curl_without_url() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("import")
+        .arg("curl")
+        .arg("curl -H 'Accept: */*'")
+        .assert()
+        .failure()
+        .stderr(contains("invalid curl command"));
+}
+
+#[test]
+fn postman_collection() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("--dry-run")
+        .arg("--verbose")
+        .arg("import")
+        .arg("postman")
+        .arg("tests/postman/collection.json")
+        .arg("--environment=tests/postman/environment.json")
+        .assert()
+        .success()
+        .stdout(
+            contains("Generated rede_api/users/get_user.toml")
+                .and(contains(r#"url = "{{host}}/users/{{id}}""#))
+                .and(contains(r#""X-{{tenant}}-Id" = "{{id}}""#))
+                .and(contains(r#"bearer = "{{token}}""#))
+                .and(contains(r#"password = "secret""#))
+                .and(contains(
+                    r#"requests = ["get_user.toml", "create_user.toml"]"#,
+                ))
+                .and(contains(r#"host = "http://localhost:8080""#))
+                .and(contains("unused").not())
+                .and(contains("Generated rede_api/.rede/envs/staging.toml"))
+                .and(contains(r#"token = "abc123""#))
+                .and(contains("old").not()),
+        );
+}
+
+#[test]
+fn postman_invalid_collection() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .arg("--no-color")
+        .arg("--dry-run")
+        .arg("import")
+        .arg("postman")
+        .arg("tests/postman/environment.json")
+        .assert()
+        .failure()
+        .stderr(contains("invalid postman file"));
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use assert_cmd::Command;
+use common::{StubRequest, StubResponse};
+use predicates::boolean::PredicateBooleanExt;
+use predicates::prelude::predicate::str::contains;
+
+// Tests of the OAuth2 authentication against a stub authorization server. The stub answers the
+// token requests with `/token` and echoes the authorization header of any other request.
+
+struct Stub {
+    url: String,
+    token_requests: Arc<Mutex<Vec<StubRequest>>>,
+}
+
+fn stub(token_status: u16) -> Stub {
+    let token_requests = Arc::new(Mutex::new(Vec::new()));
+    let requests = Arc::clone(&token_requests);
+    let url = common::stub(move |request: StubRequest| {
+        if request.line.starts_with("POST /token") {
+            let mut requests = requests.lock().unwrap();
+            requests.push(request);
+            let token = format!("stub-token-{}", requests.len());
+            StubResponse::json(format!(
+                r#"{{"access_token":"{token}","token_type":"Bearer","expires_in":3600,"refresh_token":"stub-refresh"}}"#
+            ))
+            .with_status(token_status)
+        } else {
+            let authorization = request.header("authorization").unwrap_or_default();
+            StubResponse::json(format!(r#"{{"authorization":"{authorization}"}}"#))
+        }
+    });
+
+    Stub {
+        url,
+        token_requests,
+    }
+}
+
+/// Creates a clean directory with a request using the given grant, the tokens file will be stored
+/// in this directory
+fn workspace(name: &str, stub: &Stub, grant: &str) -> PathBuf {
+    let dir = common::workspace("oauth2", name);
+    let request = format!(
+        r#"
+        [http]
+        url = "{url}/resource"
+
+        [auth.oauth2]
+        token_url = "{url}/token"
+        client_id = "rede"
+        client_secret = "{{{{secret}}}}"
+        scope = "read"
+        {grant}
+
+        [variables]
+        secret = "s3cr3t"
+        "#,
+        url = stub.url
+    );
+    write(dir.join("request.toml"), request).unwrap();
+    dir
+}
+
+fn run(dir: &PathBuf) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .current_dir(dir)
+        .args(["--no-color", "run", "--pretty-print=false", "request"])
+        .assert()
+}
+
+#[test]
+fn client_credentials_with_cache() {
+    let stub = stub(200);
+    let dir = workspace(
+        "client_credentials",
+        &stub,
+        r#"grant_type = "client_credentials""#,
+    );
+
+    run(&dir)
+        .success()
+        .stdout(contains(r#""authorization":"Bearer stub-token-1""#));
+    run(&dir)
+        .success()
+        .stdout(contains(r#""authorization":"Bearer stub-token-1""#));
+
+    let requests = stub.token_requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].text(),
+        "grant_type=client_credentials&client_id=rede&client_secret=s3cr3t&scope=read"
+    );
+    assert_eq!(requests[0].header("authorization"), None);
+    // the token response can't be decompressed, so it must not be requested compressed
+    assert_eq!(requests[0].header("accept-encoding"), None);
+    assert!(read_to_string(dir.join(".rede/tokens.toml"))
+        .unwrap()
+        .contains("stub-token-1"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = std::fs::metadata(dir.join(".rede/tokens.toml")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn password_grant() {
+    let stub = stub(200);
+    let dir = workspace(
+        "password",
+        &stub,
+        "grant_type = \"password\"\n username = \"user\"\n password = \"pass\"",
+    );
+
+    run(&dir)
+        .success()
+        .stdout(contains(r#""authorization":"Bearer stub-token-1""#));
+    let requests = stub.token_requests.lock().unwrap();
+    assert!(requests[0].text().starts_with("grant_type=password&"));
+    assert!(requests[0].text().ends_with("&username=user&password=pass"));
+}
+
+#[cfg(unix)]
+#[test]
+fn restrict_existing_tokens_file() {
+    use std::os::unix::fs::PermissionsExt;
+    let stub = stub(200);
+    let dir = workspace(
+        "existing_tokens_file",
+        &stub,
+        r#"grant_type = "client_credentials""#,
+    );
+    let tokens = dir.join(".rede/tokens.toml");
+    create_dir_all(tokens.parent().unwrap()).unwrap();
+    write(&tokens, "").unwrap();
+    std::fs::set_permissions(&tokens, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    run(&dir).success();
+    let metadata = std::fs::metadata(&tokens).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    assert!(read_to_string(tokens).unwrap().contains("stub-token-1"));
+}
+
+#[test]
+fn basic_client_authentication() {
+    let stub = stub(200);
+    let dir = workspace(
+        "basic",
+        &stub,
+        "grant_type = \"client_credentials\"\n client_auth = \"basic\"",
+    );
+
+    run(&dir)
+        .success()
+        .stdout(contains(r#""authorization":"Bearer stub-token-1""#));
+    let requests = stub.token_requests.lock().unwrap();
+    assert_eq!(
+        requests[0].text(),
+        "grant_type=client_credentials&scope=read"
+    );
+    // base64 of rede:s3cr3t
+    assert_eq!(
+        requests[0].header("authorization"),
+        Some("Basic cmVkZTpzM2NyM3Q=")
+    );
+}
+
+#[test]
+fn refresh_expired_token() {
+    let stub = stub(200);
+    let dir = workspace("refresh", &stub, r#"grant_type = "client_credentials""#);
+    create_dir_all(dir.join(".rede")).unwrap();
+    let expired = format!(
+        "[\"rede read {}/token\"]\naccess_token = \"expired\"\nexpires_at = 0\nrefresh_token = \"old-refresh\"\n",
+        stub.url
+    );
+    write(dir.join(".rede/tokens.toml"), expired).unwrap();
+
+    run(&dir)
+        .success()
+        .stdout(contains(r#""authorization":"Bearer stub-token-1""#));
+    let requests = stub.token_requests.lock().unwrap();
+    assert!(requests[0].text().starts_with("grant_type=refresh_token&"));
+    assert!(requests[0].text().ends_with("&refresh_token=old-refresh"));
+}
+
+#[test]
+fn failed_token_request() {
+    let stub = stub(401);
+    let dir = workspace("failed", &stub, r#"grant_type = "client_credentials""#);
+
+    run(&dir)
+        .failure()
+        .stderr(contains("failed oauth2 authentication").and(contains("401")));
+}
@@ -1,5 +1,9 @@
+mod common;
+
 use assert_cmd::Command;
-use predicates::prelude::predicate::str::contains;
+use common::StubResponse;
+use predicates::boolean::PredicateBooleanExt;
+use predicates::prelude::predicate::str::{contains, is_match};
 
 #[ignore]
 #[test]
@@ -32,3 +36,499 @@ fn replace_inputparams() {
         .stdout(contains(r#""http_version":"HTTP/1.1""#));
 }
 */
+
+#[test]
+fn mask_secret_inputparams() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .env("secret_token", "hunter2")
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .arg("tests/inputs/secret_input")
+        .assert()
+        .success()
+        .stdout(contains("secret_token > ********"))
+        .stdout(contains("Bearer ********").and(contains("token=********")))
+        .stdout(contains("hunter2").not());
+}
+
+#[test]
+fn mask_secret_response_url() {
+    let url = common::stub(|_| StubResponse::text("ok"));
+    let run = |args: &[&str]| {
+        Command::cargo_bin("rede")
+            .unwrap()
+            .env("secret_token", "hunter2 &x")
+            .args([
+                "--no-color",
+                "--verbose",
+                "run",
+                "--param",
+                &format!("url={url}"),
+            ])
+            .args(args)
+            .arg("tests/inputs/secret_input")
+            .env("RUST_LOG", "debug")
+            .assert()
+            .success()
+    };
+    run(&[])
+        .stdout(contains("/api/login?token=********"))
+        .stdout(contains("hunter2").not())
+        .stderr(contains("hunter2").not());
+    run(&["--format", "json"])
+        .stdout(contains(r#"/api/login?token=********","#))
+        .stdout(contains("hunter2").not());
+}
+
+#[test]
+fn format_json_ignores_quiet() {
+    let url = common::stub(|_| StubResponse::json(r#"{"hello":"world"}"#));
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--quiet", "run", "--format", "json"])
+        .args(["--pretty-print=false", "--param", &format!("url={url}")])
+        .arg("tests/suites/output/first.toml")
+        .assert()
+        .success()
+        .stdout(contains(r#""status":200"#))
+        .stdout(contains(r#""body":{"hello":"world"}"#));
+}
+
+#[test]
+fn output_file() {
+    let url = common::stub(|_| StubResponse::text("saved body"));
+    let dir = common::workspace("run", "output_file");
+    let file = dir.join("responses").join("body.txt");
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "run", "--param", &format!("url={url}")])
+        .arg("--output")
+        .arg(&file)
+        .arg("tests/suites/output/first.toml")
+        .assert()
+        .success()
+        .stdout(contains("Saved 10 bytes").and(contains("saved body").not()));
+    assert_eq!(std::fs::read_to_string(file).unwrap(), "saved body");
+}
+
+#[test]
+fn suite_output_files() {
+    let url = common::stub(|request| StubResponse::text(request.line.clone()));
+    let dir = common::workspace("run", "suite_output_files");
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "run", "--param", &format!("url={url}")])
+        .arg("--output")
+        .arg(dir.join("body.txt"))
+        .arg("tests/suites/output")
+        .assert()
+        .success()
+        .stdout(contains("2 passed, 0 failed"));
+    let body = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+    assert!(body("body.first.txt").contains("GET /api/first"));
+    assert!(body("body.second.txt").contains("GET /api/second"));
+    assert!(!dir.join("body.txt").exists());
+}
+
+#[test]
+fn closed_stdin_skips_input_params() {
+    use assert_cmd::{assert::OutputAssertExt, cargo::CommandCargoExt};
+    std::process::Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "run", "--param", "other=1"])
+        .arg("tests/inputs/replace_inputparams")
+        .stdin(std::process::Stdio::null())
+        .assert()
+        .failure()
+        .code(1)
+        .stderr(contains("unresolved placeholders").and(contains("panicked").not()));
+}
+
+#[test]
+fn replace_params() {
+    let run = |args: &[&str]| {
+        Command::cargo_bin("rede")
+            .unwrap()
+            .args(["--no-color", "--dry-run", "--verbose", "run"])
+            .args(args)
+            .arg("tests/inputs/replace_inputparams")
+            .assert()
+    };
+    run(&["--param", "endpoint=api/from_param"])
+        .success()
+        .stdout(contains("http://localhost:8080/api/from_param"));
+    run(&["--params-file", "tests/params/params.toml"])
+        .success()
+        .stdout(contains("http://localhost:8080/api/from_toml"));
+    run(&["--params-file", "tests/params/ci.env"])
+        .success()
+        .stdout(contains("http://localhost:8080/api/from_dotenv"));
+    run(&[
+        "--params-file",
+        "tests/params/ci.env",
+        "--param",
+        "endpoint=api/from_param",
+    ])
+    .success()
+    .stdout(contains("http://localhost:8080/api/from_param"));
+}
+
+#[test]
+fn dynamic_placeholders() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .args(["--param", "$uuid=fixed-id"])
+        .arg("tests/inputs/dynamic_placeholders")
+        .assert()
+        .success()
+        .stdout(contains("GET http://localhost:8080/7"))
+        .stdout(contains("authorization : Basic dXNlcjpwYXNz"))
+        .stdout(contains("x-request-id : fixed-id"));
+}
+
+#[test]
+fn invalid_dynamic_placeholder() {
+    let dir = common::workspace("run", "invalid_dynamic_placeholder");
+    let request = "[http]\nurl = \"http://localhost:8080/{{$randomInt 100 1}}\"\n";
+    std::fs::write(dir.join("request.toml"), request).unwrap();
+    Command::cargo_bin("rede")
+        .unwrap()
+        .current_dir(&dir)
+        .args(["--no-color", "--dry-run", "run", "request"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid dynamic placeholder"))
+        .stderr(contains("`$randomInt 100 1` has invalid arguments"));
+}
+
+#[test]
+fn placeholder_filters() {
+    let run = |args: &[&str]| {
+        Command::cargo_bin("rede")
+            .unwrap()
+            .args(["--no-color", "--dry-run", "--verbose", "run", "--no-input"])
+            .args(args)
+            .arg("tests/inputs/placeholder_filters")
+            .assert()
+    };
+    run(&[
+        "--param",
+        "path=API",
+        "--param",
+        "query=a b",
+        "--param",
+        "name=rede",
+    ])
+    .success()
+    .stdout(contains("GET http://localhost:8080/api"))
+    .stdout(contains("x-name : REDE"))
+    .stdout(contains("x-query : a%20b"));
+    run(&[
+        "--param",
+        "host=example.org",
+        "--param",
+        "path=a",
+        "--param",
+        "query=b",
+    ])
+    .failure()
+    .stderr(contains("unresolved: name"));
+}
+
+#[test]
+fn body_file() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .arg("tests/inputs/body_json_file")
+        .assert()
+        .success()
+        .stdout(contains("[application/json]"))
+        .stdout(contains(r#""tags": ["rede", "dog"]"#));
+}
+
+#[test]
+fn body_json_table() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .arg("tests/inputs/body_json_table")
+        .assert()
+        .success()
+        .stdout(contains(r#""age": 4,"#))
+        .stdout(contains(r#""name": "Rex","#))
+        .stdout(contains(r#""vaccinated": true"#));
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .args(["--param", r#"name=x"y"#])
+        .arg("tests/inputs/body_json_table")
+        .assert()
+        .success()
+        .stdout(contains(r#""name": "x\"y","#));
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "run", "--param", "age=abc"])
+        .arg("tests/inputs/body_json_table")
+        .assert()
+        .failure()
+        .stderr(contains("invalid typed placeholder"));
+}
+
+#[test]
+fn invalid_params() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args([
+            "run",
+            "--param",
+            "endpoint",
+            "tests/inputs/replace_inputparams",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains("expected key=value"));
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args([
+            "--no-color",
+            "run",
+            "--params-file",
+            "tests/params/broken.env",
+        ])
+        .arg("tests/inputs/replace_inputparams")
+        .assert()
+        .failure()
+        .stderr(contains("invalid params file"));
+}
+
+#[test]
+fn replace_environment_table() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .current_dir("tests/environments")
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .args(["--env", "local", "request"])
+        .assert()
+        .success()
+        .stdout(contains("http://localhost:8080/api/hello"));
+}
+
+#[test]
+fn replace_environment_file() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .current_dir("tests/environments")
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .args(["--env", "staging", "request"])
+        .assert()
+        .success()
+        .stdout(contains("http://staging.localhost:9090/api/hello"));
+}
+
+#[test]
+fn unknown_environment() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .current_dir("tests/environments")
+        .args(["--no-color", "run", "--env", "production", "request"])
+        .assert()
+        .failure()
+        .stderr(contains("unknown environment").and(contains("production")));
+}
+
+#[test]
+fn suite_alphabetical_order() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .arg("tests/suites/alphabetical")
+        .assert()
+        .success()
+        .stdout(is_match("(?s)api/first.*api/second.*Suite summary").unwrap())
+        .stdout(contains("2 passed, 0 failed").and(contains("notes.txt").not()));
+}
+
+#[test]
+fn suite_manifest_order() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--dry-run", "--verbose", "run"])
+        .arg("tests/suites/manifest")
+        .assert()
+        .success()
+        .stdout(is_match("(?s)api/login.*api/profile.*Suite summary").unwrap())
+        .stdout(contains("2 passed, 0 failed").and(contains("api/ignored").not()));
+}
+
+#[test]
+fn suite_with_failures() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "run", "tests/suites/failing"])
+        .assert()
+        .failure()
+        .stderr(contains("invalid url").and(contains("invalid file")))
+        .stderr(contains("2 of 2 requests of the suite failed"));
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "--quiet", "run", "tests/suites/failing"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(contains("invalid url").not())
+        .stderr(contains("2 of 2 requests of the suite failed"));
+}
+
+#[test]
+fn replace_captures() {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .current_dir("tests/captures")
+        .args(["--no-color", "--dry-run", "--verbose", "run", "request"])
+        .assert()
+        .success()
+        .stdout(contains("Bearer captured_token"));
+}
+
+#[test]
+fn asserts() {
+    let url = common::stub(|_| StubResponse::json(r#"{"hello":"world"}"#));
+    let run = |file: &str| {
+        Command::cargo_bin("rede")
+            .unwrap()
+            .args([
+                "--no-color",
+                "--verbose",
+                "run",
+                "--param",
+                &format!("url={url}"),
+            ])
+            .arg(file)
+            .assert()
+    };
+    run("tests/inputs/asserts")
+        .success()
+        .stdout(contains("All the asserts passed (4)"));
+    run("tests/inputs/failed_asserts")
+        .failure()
+        .stderr(contains("failed asserts").and(contains("3 of the 3 asserts")))
+        .stderr(contains("expected status 201 Created, received 200 OK"))
+        .stderr(contains(
+            "expected header Content-Type equal to \"text/plain\"",
+        ))
+        .stderr(contains("expected $.hello to be \"rede\", received world"));
+}
+
+#[test]
+fn auth_digest() {
+    // the request is redirected, and then challenged, so the URI of the response is not the one
+    // of the sent request
+    let url = common::stub(|request| {
+        if request.line.starts_with("GET /api/old") {
+            return StubResponse::text("")
+                .with_status(302)
+                .with_header("location", "/api/new?page=1");
+        }
+        match request.header("authorization") {
+            Some(authorization) => StubResponse::text(authorization),
+            None => StubResponse::text("").with_status(401).with_header(
+                "www-authenticate",
+                r#"Digest realm="rede", nonce="dcd98b7102dd2f0e", qop="auth""#,
+            ),
+        }
+    });
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "run", "--param", &format!("url={url}")])
+        .arg("tests/inputs/auth_digest")
+        .assert()
+        .success()
+        .stdout(contains(r#"Digest username="rede""#))
+        .stdout(contains(r#"uri="/api/old?page=1""#))
+        .stdout(contains(r#"realm="rede""#).and(contains("response=")));
+}
+
+#[test]
+fn captures_skipped_on_failed_asserts() {
+    let url = common::stub(|_| StubResponse::json(r#"{"token":"captured"}"#));
+    let dir = common::workspace("run", "captures_skipped");
+    let request = |status: u16| {
+        format!(
+            "[http]\nurl = \"{url}/login\"\n[asserts]\nstatus = {status}\n[captures]\ntoken.json = \"/token\"\n"
+        )
+    };
+    let run = |status: u16| {
+        std::fs::write(dir.join("request.toml"), request(status)).unwrap();
+        Command::cargo_bin("rede")
+            .unwrap()
+            .current_dir(&dir)
+            .args(["--no-color", "run", "request"])
+            .assert()
+    };
+    run(201).failure().stderr(contains("failed asserts"));
+    assert!(!dir.join(".rede/captures.toml").exists());
+    run(200).success();
+    let captures = std::fs::read_to_string(dir.join(".rede/captures.toml")).unwrap();
+    assert!(captures.contains("captured"));
+}
+
+#[test]
+fn input_params_without_prompt() {
+    let dir = common::workspace("run", "input_params_without_prompt");
+    let request = r#"
+        [http]
+        url = "http://localhost:8080/{{env}}/{{page}}"
+
+        [input_params]
+        env = { choices = ["dev", "prod"], default = "dev" }
+        page = { type = "int" }
+    "#;
+    std::fs::write(dir.join("request.toml"), request).unwrap();
+    let run = |args: &[&str]| {
+        Command::cargo_bin("rede")
+            .unwrap()
+            .current_dir(&dir)
+            .args(["--no-color", "--dry-run", "--verbose", "run", "--no-input"])
+            .args(args)
+            .arg("request")
+            .assert()
+    };
+    run(&["--param", "page=2"])
+        .success()
+        .stdout(contains("http://localhost:8080/dev/2"));
+    run(&["--param", "page=two"])
+        .failure()
+        .stderr(contains("invalid param value").and(contains("page")))
+        .stderr(contains("The value must be an integer"));
+    run(&["--param", "page=2", "--param", "env=staging"])
+        .failure()
+        .stderr(contains("The value must be one of dev, prod"));
+}
+
+#[test]
+fn suite_format_json() {
+    let url = common::stub(|_| StubResponse::json(r#"{"hello":"world"}"#));
+    let assert = Command::cargo_bin("rede")
+        .unwrap()
+        .args([
+            "--no-color",
+            "run",
+            "--format",
+            "json",
+            "--pretty-print=false",
+        ])
+        .args(["--param", &format!("url={url}")])
+        .arg("tests/suites/output")
+        .assert()
+        .success()
+        .stderr(contains("Suite summary").and(contains("2 passed, 0 failed")));
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let lines = stdout.lines().collect::<Vec<&str>>();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let envelope: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(envelope["status"], 200);
+    }
+}
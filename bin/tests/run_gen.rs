@@ -58,9 +58,14 @@ test_request!(override_content_type -> contains(r#""content-type":"application/j
 test_request!(status_if_no_body<not_found> -> contains("404"));
 test_request!(replace_variables -> contains(r#"{"hello":"world"}"#));
 test_request!(no_input "--no-input" -> contains(r#"{"hello":"world"}"#));
+test_request!(asserts -> contains(r#""hello":"world""#));
+test_request!(captures "--verbose" -> contains("hello > world").and(contains("code > 200")));
+test_request!(auth_bearer -> contains(r#""authorization":"Bearer rede-token""#));
+test_request!(auth_api_key -> contains("api_key").and(contains("rede-key")));
 test_request!(allow_unresolved<unresolved_placeholders> "--allow-unresolved" -> contains("{{message}}"));
 // todo -no-redirect, requires --verbose
 
+test_req!(auth_dry_run, success, stdout, <auth_bearer> "--dry-run", "--verbose" -> contains("Auth bearer"));
 test_req!(dry_run, success, stdout, <get_simple> "--dry-run", "--verbose" -> contains("http://localhost:8080/api/hello").and(contains(r#"{"hello":"world"}"#).not()));
 
 test_error!(missing_file -> contains("invalid [REQUEST]").and(contains("No such file or directory")));
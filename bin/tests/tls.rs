@@ -0,0 +1,162 @@
+mod common;
+
+use std::net::TcpListener;
+use std::sync::Arc;
+use std::thread;
+
+use assert_cmd::Command;
+use common::StubResponse;
+use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslVerifyMode, SslVersion};
+use predicates::prelude::predicate::str::contains;
+use predicates::prelude::PredicateBooleanExt;
+
+// Tests of the TLS configuration against a stub server using the certificates of
+// tests/assets/tls, signed by the CA of ca.pem. The stub answers with the common name of the
+// client certificate, and it can require one. When it does, it's limited to TLS 1.2 so the
+// missing certificates are rejected during the handshake.
+
+const CA: &str = "tests/assets/tls/ca.pem";
+
+fn stub(require_client_cert: bool) -> String {
+    let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+    acceptor
+        .set_private_key_file("tests/assets/tls/server.key", SslFiletype::PEM)
+        .unwrap();
+    acceptor
+        .set_certificate_chain_file("tests/assets/tls/server.pem")
+        .unwrap();
+    acceptor.set_ca_file(CA).unwrap();
+    if require_client_cert {
+        acceptor.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        acceptor
+            .set_max_proto_version(Some(SslVersion::TLS1_2))
+            .unwrap();
+    }
+    let acceptor = Arc::new(acceptor.build());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "https://localhost:{}",
+        listener.local_addr().unwrap().port()
+    );
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = acceptor.accept(stream.unwrap()) else {
+                continue;
+            };
+            let client = stream
+                .ssl()
+                .peer_certificate()
+                .and_then(|cert| {
+                    let name = cert
+                        .subject_name()
+                        .entries()
+                        .next()?
+                        .data()
+                        .as_utf8()
+                        .ok()?;
+                    Some(name.to_string())
+                })
+                .unwrap_or_else(|| String::from("anonymous"));
+            if common::read_request(&mut stream).is_some() {
+                let response = StubResponse::text(format!("client: {client}"));
+                common::write_response(&mut stream, &response);
+            }
+            let _ = stream.shutdown();
+        }
+    });
+
+    url
+}
+
+fn run(url: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+    run_request("tls", url, args)
+}
+
+fn run_request(request: &str, url: &str, args: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("rede")
+        .unwrap()
+        .args(["--no-color", "run"])
+        .args(["--param", &format!("url={url}")])
+        .args(args)
+        .arg(format!("tests/inputs/{request}"))
+        .assert()
+}
+
+#[test]
+fn server_certificate() {
+    let url = stub(false);
+    run(&url, &[])
+        .failure()
+        .stderr(contains("untrusted certificate"));
+    run(&url, &["--cacert", CA])
+        .success()
+        .stdout(contains("client: anonymous"));
+    run(&url, &["--insecure"])
+        .success()
+        .stdout(contains("client: anonymous"));
+}
+
+#[test]
+fn client_certificate() {
+    let url = stub(true);
+    run(&url, &["--cacert", CA])
+        .failure()
+        .stderr(contains("failed tls handshake"));
+    run(
+        &url,
+        &[
+            "--cacert",
+            CA,
+            "--cert",
+            "tests/assets/tls/client.pem",
+            "--key",
+            "tests/assets/tls/client.key",
+        ],
+    )
+    .success()
+    .stdout(contains("client: rede"));
+    run(
+        &url,
+        &[
+            "--cacert",
+            CA,
+            "--pkcs12",
+            "tests/assets/tls/client.p12",
+            "--cert-password",
+            "rede",
+        ],
+    )
+    .success()
+    .stdout(contains("client: rede"));
+
+    run_request("tls_table", &url, &["--param", "certs=tests/assets/tls"])
+        .success()
+        .stdout(contains("client: rede"));
+}
+
+#[test]
+fn invalid_configuration() {
+    let url = "https://localhost";
+    run(url, &["--cacert", "tests/inputs/tls.toml"])
+        .failure()
+        .stderr(contains("invalid certificate").and(contains("tls.toml could not be loaded")));
+    run(
+        url,
+        &[
+            "--pkcs12",
+            "tests/assets/tls/client.p12",
+            "--cert-password",
+            "wrong",
+        ],
+    )
+    .failure()
+    .stderr(contains("invalid certificate"));
+    run(url, &["--min-tls-version", "1.3"])
+        .failure()
+        .stderr(contains("TLS 1.3 is not supported as minimum version"));
+    run_request("tls_min_version", url, &[])
+        .failure()
+        .stderr(contains("unsupported tls version"));
+}
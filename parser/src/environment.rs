@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::schema::table::{PrimitiveTable, Table, Transform};
+
+/// File holding multiple environments, each one of them defined in its own table
+type EnvironmentsTable = Table<PrimitiveTable>;
+
+pub(crate) fn parse_environment(content: &str) -> Result<BTreeMap<String, String>, Error> {
+    let table: PrimitiveTable = toml::from_str(content)?;
+    Ok(table.into_map())
+}
+
+pub(crate) fn parse_environments(
+    content: &str,
+) -> Result<BTreeMap<String, BTreeMap<String, String>>, Error> {
+    let table: EnvironmentsTable = toml::from_str(content)?;
+    Ok(table
+        .into_iter()
+        .map(|(name, values)| (name, values.into_map()))
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn single_environment() {
+        let toml = r#"
+        host = "http://localhost:8080"
+        retries = 3
+        tags = [ "local", 1 ]
+        "#;
+        let env = parse_environment(toml).unwrap();
+        assert_eq!(env.len(), 3);
+        assert_eq!(env["host"], "http://localhost:8080");
+        assert_eq!(env["retries"], "3");
+        assert_eq!(env["tags"], "local,1");
+    }
+
+    #[test]
+    fn multiple_environments() {
+        let toml = r#"
+        [local]
+        host = "http://localhost:8080"
+
+        [staging]
+        host = "https://staging.example.org"
+        verbose = true
+        "#;
+        let envs = parse_environments(toml).unwrap();
+        assert_eq!(envs.len(), 2);
+        assert_eq!(envs["local"]["host"], "http://localhost:8080");
+        assert_eq!(envs["staging"]["host"], "https://staging.example.org");
+        assert_eq!(envs["staging"]["verbose"], "true");
+    }
+
+    #[test]
+    fn invalid_environment() {
+        assert!(parse_environment("[table]\nkey = 1").is_err());
+        assert!(parse_environments(r#"key = "not a table""#).is_err());
+    }
+}
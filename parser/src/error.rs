@@ -9,4 +9,7 @@ pub enum Error {
     /// - Bad formatting...
     #[error("{0}")]
     ParsingToml(#[from] toml::de::Error),
+    /// Error triggered while reading a file included in the request, like the `json_file` body.
+    #[error("failed to read the file {path}: {message}")]
+    ReadingFile { path: String, message: String },
 }
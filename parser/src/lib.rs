@@ -2,6 +2,9 @@
 //!
 //! The library offers the function [`rede_parser::parse_request`](parse_request)
 //! to convert a given string into a valid rede [`rede_schema::Request`](Request).
+//! It also offers [`rede_parser::parse_environment`](parse_environment) and
+//! [`rede_parser::parse_environments`](parse_environments) to read the files defining
+//! the values of the rede environments.
 //!
 //! # Example
 //!
@@ -44,11 +47,13 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::module_name_repetitions)]
 
+mod environment;
 mod error;
 mod request;
 mod schema;
 
 use crate::schema::Schema;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 #[doc(inline)]
@@ -95,3 +100,60 @@ pub fn parse_request(content: &str) -> Result<rede_schema::Request, Error> {
     let request = rede_schema::Request::try_from(schema)?;
     Ok(request)
 }
+
+/// Attempts to parse the given string into the values of a single environment. The content must be
+/// a TOML with only primitive values (or arrays of them), every value will be converted to a string.
+///
+/// # Example
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let toml = r#"
+///  host = "https://staging.example.org"
+///  page_size = 10
+/// "#;
+/// let environment = rede_parser::parse_environment(toml)?;
+/// assert_eq!(environment["host"], "https://staging.example.org");
+/// assert_eq!(environment["page_size"], "10");
+/// #    Ok(())
+/// # }
+///```
+/// # Errors
+///
+/// The function will fail if the content is not a valid TOML or if any of the values is not a
+/// primitive or array of primitives.
+pub fn parse_environment(content: &str) -> Result<BTreeMap<String, String>, Error> {
+    environment::parse_environment(content)
+}
+
+/// Attempts to parse the given string into a collection of environments. Each environment must be
+/// defined as a TOML table whose name will be used as the key of the environment.
+///
+/// # Example
+///
+/// ```
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let toml = r#"
+///  [local]
+///  host = "http://localhost:8080"
+///
+///  [staging]
+///  host = "https://staging.example.org"
+/// "#;
+/// let environments = rede_parser::parse_environments(toml)?;
+/// assert_eq!(environments["local"]["host"], "http://localhost:8080");
+/// assert_eq!(environments["staging"]["host"], "https://staging.example.org");
+/// #    Ok(())
+/// # }
+///```
+/// # Errors
+///
+/// The function will fail if the content is not a valid TOML, if it contains keys outside of the
+/// environment tables or if any of the values is not a primitive or array of primitives.
+pub fn parse_environments(
+    content: &str,
+) -> Result<BTreeMap<String, BTreeMap<String, String>>, Error> {
+    environment::parse_environments(content)
+}
@@ -13,10 +13,18 @@ impl TryFrom<Schema> for Request {
             url: schema.http.url,
             http_version: schema.http.version,
             metadata: schema.metadata.into_map(),
-            headers: schema.headers,
+            headers: schema.headers.map,
+            templated_headers: schema.headers.templated,
             query_params: schema.query_params.into_pairs(),
+            cookies: schema.cookies.into_map(),
             variables: schema.variables.into_map(),
-            body: schema.body.into(),
+            body: schema.body.try_into()?,
+            compression: schema.http.compression.map(Into::into),
+            decompress: schema.http.decompress,
+            auth: schema.auth.into(),
+            tls: schema.tls.into(),
+            asserts: schema.asserts.into(),
+            captures: schema.captures.into_map(),
 
             #[cfg(feature = "input_params")]
             input_params: schema.input_params.into_map(),
@@ -33,7 +41,7 @@ mod test {
     use crate::schema::types::{Primitive, PrimitiveArray};
     use crate::schema::{Http, Schema};
     use http::{HeaderMap, Method, Version};
-    use rede_schema::body::Body;
+    use rede_schema::body::{Body, Compression};
 
     #[cfg(feature = "input_params")]
     use rede_schema::InputParam;
@@ -70,6 +78,7 @@ mod test {
                 "ip".to_string(),
                 schema::input_param::InputParam {
                     hint: Some("hint".to_string()),
+                    ..Default::default()
                 },
             );
             input_params
@@ -82,12 +91,28 @@ mod test {
                 url: "url".to_string(),
                 method: Method::GET,
                 version: Version::HTTP_11,
+                compression: Some(schema::Compression::Gzip),
+                decompress: Some(false),
+            },
+            headers: schema::Headers {
+                map: headers,
+                templated: vec![("{{h}}".to_string(), "v".to_string())],
             },
-            headers,
             metadata: Table::new(metadata),
             query_params: Table::new(query_params),
+            cookies: Table::new(BTreeMap::from([(
+                "session".to_string(),
+                PrimitiveArray::Single(Primitive::Str("abc".to_string())),
+            )])),
             variables: Table::new(variables),
             body,
+            auth: schema::Auth::None,
+            tls: schema::Tls::default(),
+            asserts: schema::Asserts {
+                status: Some(http::StatusCode::OK),
+                ..Default::default()
+            },
+            captures: Table::default(),
 
             #[cfg(feature = "input_params")]
             input_params: Table::new(input_params),
@@ -97,8 +122,14 @@ mod test {
         assert_eq!(request.url, "url");
         assert_eq!(request.method, Method::GET);
         assert_eq!(request.http_version, Version::HTTP_11);
+        assert_eq!(request.compression, Some(Compression::Gzip));
+        assert_eq!(request.decompress, Some(false));
         assert_eq!(request.metadata["name"], "test");
         assert_eq!(request.headers["Header"], "Value");
+        assert_eq!(
+            request.templated_headers,
+            vec![("{{h}}".to_string(), "v".to_string())]
+        );
         assert_eq!(
             request.query_params,
             vec![
@@ -106,7 +137,9 @@ mod test {
                 ("qp".to_string(), "1".to_string()),
             ]
         );
+        assert_eq!(request.cookies["session"], "abc");
         assert_eq!(request.variables["pp"], "value");
+        assert_eq!(request.asserts.status, Some(http::StatusCode::OK));
         assert_eq!(
             request.body,
             Body::Binary {
@@ -120,6 +153,7 @@ mod test {
             request.input_params["ip"],
             InputParam {
                 hint: Some("hint".to_string()),
+                ..Default::default()
             }
         );
     }
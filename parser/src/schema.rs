@@ -1,17 +1,27 @@
 use std::str::FromStr;
 
-use http::{HeaderMap, Method, Version};
+use http::{Method, Version};
 use serde::Deserialize;
 
-pub(crate) use body::Body;
+pub(crate) use asserts::Asserts;
+pub(crate) use auth::Auth;
+pub(crate) use body::{Body, Compression};
+pub(crate) use headers::Headers;
+pub(crate) use tls::Tls;
 
 use crate::error::Error;
-use crate::schema::table::PrimitiveTable;
+use crate::schema::table::{CapturesTable, PrimitiveTable};
 
 #[cfg(feature = "input_params")]
 use crate::schema::table::InputParamsTable;
 
+mod asserts;
+mod auth;
 mod body;
+mod capture;
+mod headers;
+mod json;
+mod tls;
 
 pub(crate) mod table;
 pub(crate) mod types;
@@ -26,14 +36,24 @@ pub(crate) struct Schema {
     pub http: Http,
     #[serde(default)]
     pub metadata: PrimitiveTable,
-    #[serde(with = "http_serde::header_map", default)]
-    pub headers: HeaderMap,
+    #[serde(default)]
+    pub headers: Headers,
     #[serde(alias = "queryparams", alias = "query-params", default)]
     pub query_params: PrimitiveTable,
     #[serde(default)]
+    pub cookies: PrimitiveTable,
+    #[serde(default)]
     pub body: Body,
     #[serde(default)]
+    pub auth: Auth,
+    #[serde(default)]
+    pub tls: Tls,
+    #[serde(default)]
     pub variables: PrimitiveTable,
+    #[serde(default)]
+    pub asserts: Asserts,
+    #[serde(alias = "outputs", default)]
+    pub captures: CapturesTable,
 
     #[cfg(feature = "input_params")]
     #[serde(alias = "inputparams", alias = "input-params", default)]
@@ -48,6 +68,9 @@ pub(crate) struct Http {
     pub method: Method,
     #[serde(with = "http_serde::version", default)]
     pub version: Version,
+    #[serde(alias = "compress", default)]
+    pub compression: Option<Compression>,
+    pub decompress: Option<bool>,
 }
 
 impl FromStr for Schema {
@@ -73,6 +96,8 @@ mod test {
     method = "GET"
     url = "https://example.org/api"
     version = "HTTP/1.1"
+    compression = "br"
+    decompress = false
 
     [metadata]
     name = "Test request"
@@ -89,6 +114,10 @@ mod test {
     array = [ "first", "second" ]
     boolean = true
 
+    [cookies]
+    session = "{{session}}"
+    theme = "dark"
+
     [variables]
     string = "string"
     integer = 5
@@ -103,6 +132,23 @@ mod test {
     }
     """
 
+    [auth]
+    bearer = "{{token}}"
+
+    [tls]
+    ca_cert = "certs/ca.pem"
+    insecure = true
+
+    [asserts]
+    status = 200
+    max_response_time = 300
+    headers.Content-Type.contains = "toml"
+    body."$.key" = "value"
+
+    [captures]
+    token.json = "/token"
+    status = "status"
+
     [input-params]
     host = { hint = "Host name", default = "localhost" }
     no-default = { hint = "This has no default value" }
@@ -116,6 +162,8 @@ mod test {
         assert_eq!(schema.http.url, "https://example.org/api");
         assert_eq!(schema.http.method, Method::GET);
         assert_eq!(schema.http.version, Version::HTTP_11);
+        assert_eq!(schema.http.compression, Some(Compression::Brotli));
+        assert_eq!(schema.http.decompress, Some(false));
         assert_eq!(schema.metadata.0.len(), 2);
         assert_eq!(
             schema.metadata.0["name"],
@@ -127,9 +175,9 @@ mod test {
                 "Request with all supported options".to_string()
             ))
         );
-        assert_eq!(schema.headers.len(), 2);
-        assert_eq!(schema.headers["Content-Type"], "application/toml");
-        assert_eq!(schema.headers["Api-Version"], "v2");
+        assert_eq!(schema.headers.map.len(), 2);
+        assert_eq!(schema.headers.map["Content-Type"], "application/toml");
+        assert_eq!(schema.headers.map["Api-Version"], "v2");
         assert_eq!(schema.query_params.0.len(), 5);
         assert_eq!(
             schema.query_params.0["string"],
@@ -154,6 +202,11 @@ mod test {
                 Primitive::Str("second".into()),
             ])
         );
+        assert_eq!(schema.cookies.0.len(), 2);
+        assert_eq!(
+            schema.cookies.0["session"],
+            PrimitiveArray::Single(Primitive::Str("{{session}}".to_string()))
+        );
         assert_eq!(schema.variables.0.len(), 5);
         assert_eq!(
             schema.variables.0["string"],
@@ -177,6 +230,13 @@ mod test {
         );
         let body: Body = schema.body;
         assert!(matches!(body, Body::Raw(content) if content.contains(r#""key": "value""#)));
+        assert!(matches!(schema.auth, Auth::Bearer(_)));
+        assert_ne!(schema.tls, Tls::default());
+        assert_eq!(schema.asserts.status, Some(http::StatusCode::OK));
+        assert_eq!(schema.asserts.max_response_time, Some(300));
+        assert_eq!(schema.asserts.headers.len(), 1);
+        assert_eq!(schema.asserts.body.len(), 1);
+        assert_eq!(schema.captures.len(), 2);
 
         #[cfg(feature = "input_params")]
         {
@@ -185,9 +245,11 @@ mod test {
                 schema.input_params.0["host"],
                 InputParam {
                     hint: Some("Host name".to_string()),
+                    default: Some("localhost".to_string()),
+                    ..Default::default()
                 }
             );
-            assert_eq!(schema.input_params.0["empty"], InputParam { hint: None });
+            assert_eq!(schema.input_params.0["empty"], InputParam::default());
             assert_eq!(
                 schema.input_params.0["no-default"].hint,
                 Some("This has no default value".to_string())
@@ -215,11 +277,18 @@ mod test {
         let schema = Schema::from_str(toml).unwrap();
         assert_eq!(schema.http.method, Method::GET);
         assert_eq!(schema.http.version, Version::HTTP_11);
+        assert_eq!(schema.http.compression, None);
+        assert_eq!(schema.http.decompress, None);
         assert!(schema.metadata.0.is_empty());
-        assert!(schema.headers.is_empty());
+        assert!(schema.headers.map.is_empty());
         assert!(schema.query_params.0.is_empty());
+        assert!(schema.cookies.0.is_empty());
         assert!(schema.variables.0.is_empty());
         assert_eq!(schema.body, Body::None);
+        assert_eq!(schema.auth, Auth::None);
+        assert_eq!(schema.tls, Tls::default());
+        assert_eq!(schema.asserts, Asserts::default());
+        assert!(schema.captures.0.is_empty());
 
         #[cfg(feature = "input_params")]
         assert!(schema.input_params.0.is_empty());
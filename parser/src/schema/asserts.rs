@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use http::StatusCode;
+use rede_schema::asserts::HeaderAssert as SchemaHA;
+use rede_schema::Asserts as SchemaAsserts;
+use serde::Deserialize;
+
+use crate::schema::table::{HeaderAssertsTable, PrimitiveTable, Transform};
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub(crate) struct Asserts {
+    #[serde(with = "http_serde::option::status_code", default)]
+    pub status: Option<StatusCode>,
+    #[serde(default)]
+    pub headers: HeaderAssertsTable,
+    #[serde(default)]
+    pub body: PrimitiveTable,
+    /// Maximum response time in milliseconds
+    #[serde(alias = "maxresponsetime", alias = "max-response-time", default)]
+    pub max_response_time: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum HeaderAssert {
+    Equals(String),
+    Contains(String),
+}
+
+impl From<Asserts> for SchemaAsserts {
+    fn from(value: Asserts) -> Self {
+        Self {
+            status: value.status,
+            headers: value.headers.into_map(),
+            body: value.body.into_map(),
+            max_response_time: value.max_response_time.map(Duration::from_millis),
+        }
+    }
+}
+
+impl From<HeaderAssert> for SchemaHA {
+    fn from(value: HeaderAssert) -> Self {
+        match value {
+            HeaderAssert::Equals(value) => SchemaHA::Equals(value),
+            HeaderAssert::Contains(value) => SchemaHA::Contains(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::schema::types::{Primitive, PrimitiveArray};
+
+    #[test]
+    fn deserialize() {
+        let toml = r#"
+        status = 201
+        max-response-time = 500
+
+        [headers]
+        Content-Type.equals = "application/json"
+        Location.contains = "/api/notes/"
+
+        [body]
+        "$.id" = 1
+        "$.tags[0]" = "rede"
+        "#;
+        let asserts: Asserts = toml::from_str(toml).unwrap();
+        assert_eq!(asserts.status, Some(StatusCode::CREATED));
+        assert_eq!(asserts.max_response_time, Some(500));
+        assert_eq!(
+            asserts.headers.0["Content-Type"],
+            HeaderAssert::Equals("application/json".to_string())
+        );
+        assert_eq!(
+            asserts.headers.0["Location"],
+            HeaderAssert::Contains("/api/notes/".to_string())
+        );
+        assert_eq!(
+            asserts.body.0["$.id"],
+            PrimitiveArray::Single(Primitive::Int(1))
+        );
+        assert_eq!(
+            asserts.body.0["$.tags[0]"],
+            PrimitiveArray::Single(Primitive::Str("rede".to_string()))
+        );
+    }
+
+    #[test]
+    fn deserialize_empty() {
+        let asserts: Asserts = toml::from_str("").unwrap();
+        assert_eq!(asserts, Asserts::default());
+    }
+
+    #[test]
+    fn invalid_status() {
+        assert!(toml::from_str::<Asserts>("status = 1000").is_err());
+        assert!(toml::from_str::<Asserts>(r#"headers.Accept.starts = "text""#).is_err());
+    }
+
+    #[test]
+    fn into_schema() {
+        let toml = r#"
+        status = 200
+        max_response_time = 1000
+        headers.Accept.contains = "json"
+        body."$.name" = "rede"
+        "#;
+        let asserts: SchemaAsserts = toml::from_str::<Asserts>(toml).unwrap().into();
+        assert_eq!(asserts.status, Some(StatusCode::OK));
+        assert_eq!(asserts.max_response_time, Some(Duration::from_secs(1)));
+        assert_eq!(
+            asserts.headers["Accept"],
+            SchemaHA::Contains("json".to_string())
+        );
+        assert_eq!(asserts.body["$.name"], "rede");
+    }
+}
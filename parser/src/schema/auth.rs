@@ -0,0 +1,321 @@
+use rede_schema::auth::{
+    ApiKeyLocation as SchemaLocation, ClientAuth as SchemaClientAuth, Grant as SchemaGrant,
+    OAuth2 as SchemaOAuth2,
+};
+use rede_schema::Auth as SchemaAuth;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Auth {
+    #[default]
+    None,
+    Basic(Credentials),
+    Bearer(Token),
+    Digest(Credentials),
+    #[serde(alias = "apikey", alias = "api-key")]
+    ApiKey(ApiKey),
+    #[serde(rename = "oauth2", alias = "oauth")]
+    OAuth2(OAuth2),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub(crate) struct Credentials {
+    #[serde(alias = "user")]
+    pub username: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+/// The token can be given directly, `bearer = "token"`, or as a table, `bearer.token = "token"`
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub(crate) enum Token {
+    Value(String),
+    Table { token: String },
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub(crate) struct ApiKey {
+    pub key: String,
+    pub value: String,
+    #[serde(rename = "in", alias = "location", default)]
+    pub location: Location,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Location {
+    #[default]
+    Header,
+    #[serde(alias = "query_param", alias = "query_params")]
+    Query,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub(crate) struct OAuth2 {
+    #[serde(alias = "token_endpoint")]
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: String,
+    #[serde(default)]
+    pub scope: String,
+    #[serde(default, alias = "client_authentication")]
+    pub client_auth: ClientAuth,
+    #[serde(flatten)]
+    pub grant: Grant,
+}
+
+/// The names of RFC 7591 are also accepted, like `client_secret_basic`
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ClientAuth {
+    #[serde(alias = "client_secret_basic")]
+    Basic,
+    #[default]
+    #[serde(alias = "client_secret_post")]
+    Post,
+}
+
+/// The grant is selected with `grant_type`, and each one requires its own fields
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "grant_type", rename_all = "snake_case")]
+pub(crate) enum Grant {
+    ClientCredentials,
+    Password {
+        #[serde(alias = "user")]
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+    RefreshToken {
+        refresh_token: String,
+    },
+}
+
+impl From<Auth> for SchemaAuth {
+    fn from(value: Auth) -> Self {
+        match value {
+            Auth::None => SchemaAuth::None,
+            Auth::Basic(Credentials { username, password }) => {
+                SchemaAuth::Basic { username, password }
+            }
+            Auth::Bearer(Token::Value(token) | Token::Table { token }) => {
+                SchemaAuth::Bearer { token }
+            }
+            Auth::Digest(Credentials { username, password }) => {
+                SchemaAuth::Digest { username, password }
+            }
+            Auth::ApiKey(ApiKey {
+                key,
+                value,
+                location,
+            }) => SchemaAuth::ApiKey {
+                key,
+                value,
+                location: match location {
+                    Location::Header => SchemaLocation::Header,
+                    Location::Query => SchemaLocation::Query,
+                },
+            },
+            Auth::OAuth2(oauth2) => SchemaAuth::OAuth2(SchemaOAuth2 {
+                token_url: oauth2.token_url,
+                client_id: oauth2.client_id,
+                client_secret: oauth2.client_secret,
+                scope: oauth2.scope,
+                client_auth: match oauth2.client_auth {
+                    ClientAuth::Basic => SchemaClientAuth::Basic,
+                    ClientAuth::Post => SchemaClientAuth::Post,
+                },
+                grant: match oauth2.grant {
+                    Grant::ClientCredentials => SchemaGrant::ClientCredentials,
+                    Grant::Password { username, password } => {
+                        SchemaGrant::Password { username, password }
+                    }
+                    Grant::RefreshToken { refresh_token } => {
+                        SchemaGrant::RefreshToken { refresh_token }
+                    }
+                },
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Parent {
+        auth: Auth,
+    }
+
+    fn parse(toml: &str) -> SchemaAuth {
+        toml::from_str::<Parent>(toml).unwrap().auth.into()
+    }
+
+    #[test]
+    fn deserialize_basic() {
+        let toml = r#"
+        [auth.basic]
+        username = "user"
+        password = "{{password}}"
+        "#;
+        assert_eq!(
+            parse(toml),
+            SchemaAuth::Basic {
+                username: "user".to_string(),
+                password: "{{password}}".to_string()
+            }
+        );
+
+        let toml = r#"auth.basic.user = "user""#;
+        assert_eq!(
+            parse(toml),
+            SchemaAuth::Basic {
+                username: "user".to_string(),
+                password: String::new()
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_bearer() {
+        let expected = SchemaAuth::Bearer {
+            token: "{{token}}".to_string(),
+        };
+        assert_eq!(parse(r#"auth.bearer = "{{token}}""#), expected);
+        assert_eq!(parse(r#"auth.bearer.token = "{{token}}""#), expected);
+    }
+
+    #[test]
+    fn deserialize_digest() {
+        let toml = r#"
+        [auth.digest]
+        username = "user"
+        password = "pass"
+        "#;
+        assert_eq!(
+            parse(toml),
+            SchemaAuth::Digest {
+                username: "user".to_string(),
+                password: "pass".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_api_key() {
+        let toml = r#"
+        [auth.api_key]
+        key = "X-API-Key"
+        value = "{{key}}"
+        "#;
+        assert_eq!(
+            parse(toml),
+            SchemaAuth::ApiKey {
+                key: "X-API-Key".to_string(),
+                value: "{{key}}".to_string(),
+                location: SchemaLocation::Header,
+            }
+        );
+
+        let toml = r#"auth.apikey = { key = "api_key", value = "abc", in = "query" }"#;
+        assert_eq!(
+            parse(toml),
+            SchemaAuth::ApiKey {
+                key: "api_key".to_string(),
+                value: "abc".to_string(),
+                location: SchemaLocation::Query,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_oauth2() {
+        let toml = r#"
+        [auth.oauth2]
+        grant_type = "client_credentials"
+        token_url = "{{host}}/oauth/token"
+        client_id = "rede"
+        client_secret = "{{secret}}"
+        scope = "read write"
+        "#;
+        assert_eq!(
+            parse(toml),
+            SchemaAuth::OAuth2(SchemaOAuth2 {
+                token_url: "{{host}}/oauth/token".to_string(),
+                client_id: "rede".to_string(),
+                client_secret: "{{secret}}".to_string(),
+                scope: "read write".to_string(),
+                client_auth: SchemaClientAuth::Post,
+                grant: SchemaGrant::ClientCredentials,
+            })
+        );
+
+        let toml = r#"
+        [auth.oauth2]
+        grant_type = "password"
+        token_url = "http://localhost/token"
+        client_id = "rede"
+        client_auth = "client_secret_basic"
+        username = "user"
+        password = "pass"
+        "#;
+        let SchemaAuth::OAuth2(oauth2) = parse(toml) else {
+            panic!("expected oauth2 auth");
+        };
+        assert!(oauth2.client_secret.is_empty());
+        assert_eq!(oauth2.client_auth, SchemaClientAuth::Basic);
+        assert_eq!(
+            oauth2.grant,
+            SchemaGrant::Password {
+                username: "user".to_string(),
+                password: "pass".to_string()
+            }
+        );
+
+        let toml = r#"
+        [auth.oauth2]
+        grant_type = "refresh_token"
+        token_url = "http://localhost/token"
+        client_id = "rede"
+        refresh_token = "{{refresh}}"
+        "#;
+        let SchemaAuth::OAuth2(oauth2) = parse(toml) else {
+            panic!("expected oauth2 auth");
+        };
+        assert_eq!(
+            oauth2.grant,
+            SchemaGrant::RefreshToken {
+                refresh_token: "{{refresh}}".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn oauth2_missing_grant_fields() {
+        let toml = r#"
+        [auth.oauth2]
+        grant_type = "refresh_token"
+        token_url = "http://localhost/token"
+        client_id = "rede"
+        "#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+
+        let toml = r#"
+        [auth.oauth2]
+        grant_type = "implicit"
+        token_url = "http://localhost/token"
+        client_id = "rede"
+        "#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+    }
+
+    #[test]
+    fn unknown_auth() {
+        let toml = r#"auth.ntlm.user = "user""#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+    }
+}
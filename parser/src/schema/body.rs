@@ -1,10 +1,14 @@
 use std::str::FromStr;
 
+use crate::error::Error;
+use crate::schema::json::{to_json, Json};
 use crate::schema::table::{FormDataTable, PrimitiveTable, Transform};
 use crate::schema::types::PrimitiveArray;
+use http::HeaderMap;
 use mime::Mime;
+use rede_schema::body::GraphQL as SchemaGraphQL;
 use rede_schema::Body as SchemaBody;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 
 #[derive(Debug, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -14,9 +18,15 @@ pub(crate) enum Body {
     #[serde(alias = "text")]
     Raw(String),
     #[serde(alias = "json")]
-    Json(String),
+    Json(Json),
     #[serde(alias = "xml")]
     Xml(String),
+    #[serde(alias = "text_file", alias = "text-file", alias = "raw-file")]
+    RawFile(String),
+    #[serde(alias = "json-file")]
+    JsonFile(String),
+    #[serde(alias = "xml-file")]
+    XmlFile(String),
     #[serde(alias = "gzip", alias = "gz")]
     Gzip(String),
     #[serde(alias = "zip")]
@@ -44,31 +54,117 @@ pub(crate) enum Body {
         alias = "form-urlencoded"
     )]
     XFormUrlEncoded(PrimitiveTable),
+    #[serde(rename = "graphql")]
+    GraphQL(GraphQL),
 }
 
 #[derive(Debug, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+#[serde(deny_unknown_fields)]
+pub(crate) struct GraphQL {
+    #[serde(flatten)]
+    query: Query,
+    variables: Option<toml::Table>,
+    #[serde(alias = "operationName")]
+    operation_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Query {
+    Query(String),
+    /// Path to a `.graphql` file with the query
+    #[serde(alias = "file")]
+    QueryFile(String),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(try_from = "FormDataFields")]
+pub(crate) struct FormDataPart {
+    value: FormDataValue,
+    filename: Option<String>,
+    content_type: Option<Mime>,
+    headers: HeaderMap,
+}
+
+/// Keys accepted by a part of the multipart body, where the value is either `text` or `file`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormDataFields {
+    text: Option<PrimitiveArray>,
+    file: Option<String>,
+    filename: Option<String>,
+    #[serde(
+        default,
+        alias = "content-type",
+        alias = "mime",
+        deserialize_with = "deserialize_mime"
+    )]
+    content_type: Option<Mime>,
+    #[serde(default, with = "http_serde::header_map")]
+    headers: HeaderMap,
+}
+
+impl TryFrom<FormDataFields> for FormDataPart {
+    type Error = &'static str;
+
+    fn try_from(fields: FormDataFields) -> Result<Self, Self::Error> {
+        let value = match (fields.text, fields.file) {
+            (Some(text), None) => FormDataValue::Text(text),
+            (None, Some(path)) => FormDataValue::File(path),
+            _ => return Err("wanted exactly 1 element of `text` or `file`"),
+        };
+        Ok(Self {
+            value,
+            filename: fields.filename,
+            content_type: fields.content_type,
+            headers: fields.headers,
+        })
+    }
+}
+
+fn deserialize_mime<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Mime>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|mime| Mime::from_str(&mime).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+#[derive(Debug, PartialEq)]
 pub(crate) enum FormDataValue {
     Text(PrimitiveArray),
     File(String),
 }
 
-impl From<Body> for SchemaBody {
-    fn from(value: Body) -> Self {
-        match value {
+impl TryFrom<Body> for SchemaBody {
+    type Error = Error;
+
+    fn try_from(value: Body) -> Result<Self, Self::Error> {
+        Ok(match value {
             Body::None => SchemaBody::None,
             Body::Raw(content) => SchemaBody::Raw {
                 content,
                 mime: mime::TEXT_PLAIN_UTF_8,
             },
-            Body::Json(content) => SchemaBody::Raw {
+            Body::Json(Json::Text(content)) => SchemaBody::Raw {
                 content,
                 mime: mime::APPLICATION_JSON,
             },
+            Body::Json(Json::Value(value)) => SchemaBody::Json(to_json(value)),
             Body::Xml(content) => SchemaBody::Raw {
                 content,
                 mime: mime::TEXT_XML,
             },
+            Body::RawFile(path) => SchemaBody::Raw {
+                content: read(path)?,
+                mime: mime::TEXT_PLAIN_UTF_8,
+            },
+            Body::JsonFile(path) => SchemaBody::Raw {
+                content: read(path)?,
+                mime: mime::APPLICATION_JSON,
+            },
+            Body::XmlFile(path) => SchemaBody::Raw {
+                content: read(path)?,
+                mime: mime::TEXT_XML,
+            },
             Body::Gzip(path) => SchemaBody::Binary {
                 path,
                 mime: Mime::from_str("application/gzip").unwrap(),
@@ -99,16 +195,59 @@ impl From<Body> for SchemaBody {
             },
             Body::FormData(table) => SchemaBody::FormData(table.into_map()),
             Body::XFormUrlEncoded(table) => SchemaBody::XFormUrlEncoded(table.into_map()),
-        }
+            Body::GraphQL(graphql) => SchemaBody::GraphQL(SchemaGraphQL {
+                query: match graphql.query {
+                    Query::Query(query) => query,
+                    Query::QueryFile(path) => read(path)?,
+                },
+                variables: graphql.variables.map(|variables| to_json(variables.into())),
+                operation_name: graphql.operation_name,
+            }),
+        })
     }
 }
 
-use rede_schema::body::FormDataValue as SchemaFDV;
-impl Transform<FormDataValue, SchemaFDV> for FormDataTable {
-    fn map_value(value: FormDataValue) -> SchemaFDV {
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Compression {
+    Gzip,
+    Deflate,
+    #[serde(alias = "br")]
+    Brotli,
+    Zstd,
+}
+
+use rede_schema::body::Compression as SchemaCompression;
+impl From<Compression> for SchemaCompression {
+    fn from(value: Compression) -> Self {
         match value {
-            FormDataValue::Text(value) => SchemaFDV::Text(value.into()),
-            FormDataValue::File(path) => SchemaFDV::File(path),
+            Compression::Gzip => SchemaCompression::Gzip,
+            Compression::Deflate => SchemaCompression::Deflate,
+            Compression::Brotli => SchemaCompression::Brotli,
+            Compression::Zstd => SchemaCompression::Zstd,
+        }
+    }
+}
+
+/// Reads the content of a body file, the path is relative to the working directory
+fn read(path: String) -> Result<String, Error> {
+    std::fs::read_to_string(&path).map_err(|e| Error::ReadingFile {
+        path,
+        message: e.to_string(),
+    })
+}
+
+use rede_schema::body::{FormDataPart as SchemaFDP, FormDataValue as SchemaFDV};
+impl Transform<FormDataPart, SchemaFDP> for FormDataTable {
+    fn map_value(part: FormDataPart) -> SchemaFDP {
+        SchemaFDP {
+            value: match part.value {
+                FormDataValue::Text(value) => SchemaFDV::Text(value.into()),
+                FormDataValue::File(path) => SchemaFDV::File(path),
+            },
+            filename: part.filename,
+            content_type: part.content_type,
+            headers: part.headers,
         }
     }
 }
@@ -152,13 +291,99 @@ mod test {
         assert!(matches!(&body, Body::FormData(map) if map.len() == 2));
         if let Body::FormData(map) = &body {
             assert_eq!(
-                map["raw"],
+                map["raw"].value,
                 FormDataValue::Text(PrimitiveArray::Single(Primitive::Str("raw".to_string())))
             );
-            assert_eq!(map["binary"], FormDataValue::File("path".to_string()));
+            assert_eq!(map["binary"].value, FormDataValue::File("path".to_string()));
         }
     }
 
+    #[test]
+    fn form_data_part() {
+        let toml = r#"
+        [body.form_data]
+        avatar = { file = "avatar.png", filename = "me.png", content_type = "image/png" }
+        [body.form_data.meta]
+        text = "{}"
+        content-type = "application/json"
+        headers = { X-Part = "meta" }
+        "#;
+        let body = toml::from_str::<Parent>(toml).unwrap().body;
+        let Body::FormData(map) = &body else {
+            panic!("expected form data, got {body:?}");
+        };
+        let avatar = &map["avatar"];
+        assert_eq!(avatar.value, FormDataValue::File("avatar.png".to_string()));
+        assert_eq!(avatar.filename.as_deref(), Some("me.png"));
+        assert_eq!(avatar.content_type, Some(mime::IMAGE_PNG));
+        assert!(avatar.headers.is_empty());
+        let meta = &map["meta"];
+        assert_eq!(meta.filename, None);
+        assert_eq!(meta.content_type, Some(mime::APPLICATION_JSON));
+        assert_eq!(meta.headers["x-part"], "meta");
+
+        let toml = r#"
+        [body.form_data]
+        avatar = { file = "avatar.png", content_type = "not a mime" }
+        "#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+    }
+
+    #[test]
+    fn body_file() {
+        let toml = r#"body.json_file = "Cargo.toml""#;
+        let body = toml::from_str::<Parent>(toml).unwrap().body;
+        assert_eq!(body, Body::JsonFile("Cargo.toml".to_string()));
+        let SchemaBody::Raw { content, mime } = SchemaBody::try_from(body).unwrap() else {
+            panic!("body file is not raw")
+        };
+        assert_eq!(mime, mime::APPLICATION_JSON);
+        assert!(content.contains("name = \"rede_parser\""));
+
+        let toml = r#"body.text-file = "missing.txt""#;
+        let body = toml::from_str::<Parent>(toml).unwrap().body;
+        assert_eq!(body, Body::RawFile("missing.txt".to_string()));
+        let err = SchemaBody::try_from(body).unwrap_err();
+        assert!(matches!(err, Error::ReadingFile { path, .. } if path == "missing.txt"));
+    }
+
+    #[test]
+    fn graphql() {
+        let toml = r#"
+        [body.graphql]
+        query = "query Pet($id: ID!) { pet(id: $id) { name } }"
+        variables = { id = "{{id:int}}" }
+        operationName = "Pet"
+        "#;
+        let body = toml::from_str::<Parent>(toml).unwrap().body;
+        let SchemaBody::GraphQL(graphql) = SchemaBody::try_from(body).unwrap() else {
+            panic!("body is not graphql")
+        };
+        assert_eq!(
+            graphql.query,
+            "query Pet($id: ID!) { pet(id: $id) { name } }"
+        );
+        assert_eq!(
+            graphql.variables.unwrap(),
+            serde_json::json!({ "id": "{{id:int}}" })
+        );
+        assert_eq!(graphql.operation_name.unwrap(), "Pet");
+
+        let toml = r#"body.graphql.query_file = "Cargo.toml""#;
+        let body = toml::from_str::<Parent>(toml).unwrap().body;
+        let SchemaBody::GraphQL(graphql) = SchemaBody::try_from(body).unwrap() else {
+            panic!("body is not graphql")
+        };
+        assert!(graphql.query.contains("rede_parser"));
+        assert_eq!(graphql.variables, None);
+        assert_eq!(graphql.operation_name, None);
+
+        let toml = "body.graphql.variables = { id = 1 }";
+        assert!(toml::from_str::<Parent>(toml).is_err());
+        let toml = r#"body.graphql = { query = "{ pets }", unknown = 1 }"#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+    }
+
     #[test]
     fn only_one_type() {
         let toml = r#"
@@ -0,0 +1,81 @@
+use rede_schema::Capture as SchemaCapture;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Capture {
+    Json(String),
+    Header(String),
+    Regex(#[serde(deserialize_with = "deserialize_regex")] String),
+    Status,
+}
+
+/// Ensures that the pattern of the regex captures compiles
+fn deserialize_regex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let pattern = String::deserialize(deserializer)?;
+    Regex::new(&pattern)
+        .map_err(|e| serde::de::Error::custom(format!("invalid regex `{pattern}`: {e}")))?;
+    Ok(pattern)
+}
+
+impl From<Capture> for SchemaCapture {
+    fn from(value: Capture) -> Self {
+        match value {
+            Capture::Json(pointer) => SchemaCapture::Json(pointer),
+            Capture::Header(name) => SchemaCapture::Header(name),
+            Capture::Regex(regex) => SchemaCapture::Regex(regex),
+            Capture::Status => SchemaCapture::Status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn deserialize() {
+        let toml = r#"
+        token.json = "/access_token"
+        session.header = "X-Session"
+        id.regex = "id=(\\d+)"
+        code = "status"
+        "#;
+        let captures: BTreeMap<String, Capture> = toml::from_str(toml).unwrap();
+        assert_eq!(captures.len(), 4);
+        assert_eq!(
+            captures["token"],
+            Capture::Json("/access_token".to_string())
+        );
+        assert_eq!(
+            captures["session"],
+            Capture::Header("X-Session".to_string())
+        );
+        assert_eq!(captures["id"], Capture::Regex(r"id=(\d+)".to_string()));
+        assert_eq!(captures["code"], Capture::Status);
+    }
+
+    #[test]
+    fn only_one_type() {
+        let toml = r#"
+        token.json = "/access_token"
+        token.header = "X-Token"
+        "#;
+        let err = toml::from_str::<BTreeMap<String, Capture>>(toml)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("wanted exactly 1 element"));
+        assert!(toml::from_str::<BTreeMap<String, Capture>>(r#"code = "body""#).is_err());
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let err = toml::from_str::<BTreeMap<String, Capture>>(r#"id.regex = "id=(\\d+""#)
+            .err()
+            .unwrap();
+        assert!(err.message().contains(r"invalid regex `id=(\d+`"));
+    }
+}
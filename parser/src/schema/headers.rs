@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use http::{HeaderMap, HeaderName, HeaderValue};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+/// Headers of the request. The names containing placeholders are not valid header names until
+/// they are rendered, so those headers are kept apart as text.
+#[derive(Debug, Default)]
+pub(crate) struct Headers {
+    pub map: HeaderMap,
+    pub templated: Vec<(String, String)>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Values {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl<'de> Deserialize<'de> for Headers {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut headers = Headers::default();
+        for (name, values) in BTreeMap::<String, Values>::deserialize(deserializer)? {
+            let values = match values {
+                Values::Single(value) => vec![value],
+                Values::Multiple(values) => values,
+            };
+
+            if name.contains("{{") {
+                headers
+                    .templated
+                    .extend(values.into_iter().map(|v| (name.clone(), v)));
+                continue;
+            }
+
+            let header = HeaderName::from_str(&name)
+                .map_err(|_| D::Error::custom(format!("invalid header name: {name}")))?;
+            for value in values {
+                let value = HeaderValue::from_str(&value).map_err(|_| {
+                    D::Error::custom(format!("invalid value of the header {name}: {value}"))
+                })?;
+                headers.map.append(&header, value);
+            }
+        }
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Parent {
+        headers: Headers,
+    }
+
+    #[test]
+    fn deserialize() {
+        let toml = r#"
+        [headers]
+        Content-Type = "application/json"
+        Accept = ["text/html", "application/json"]
+        "{{tenant}}-Id" = "{{id}}"
+        "X-{{tenant}}-Tag" = ["a", "b"]
+        "#;
+        let headers = toml::from_str::<Parent>(toml).unwrap().headers;
+        assert_eq!(headers.map["Content-Type"], "application/json");
+        assert_eq!(headers.map.get_all("Accept").iter().count(), 2);
+        assert_eq!(
+            headers.templated,
+            vec![
+                ("X-{{tenant}}-Tag".to_string(), "a".to_string()),
+                ("X-{{tenant}}-Tag".to_string(), "b".to_string()),
+                ("{{tenant}}-Id".to_string(), "{{id}}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_header() {
+        let toml = r#"headers = { "Bad Name" = "value" }"#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+        let toml = r#"headers = { Name = "line\nbreak" }"#;
+        assert!(toml::from_str::<Parent>(toml).is_err());
+    }
+}
@@ -1,14 +1,138 @@
-use rede_schema::InputParam as SchemaIP;
+use std::fmt::{Display, Formatter};
+
+use http::Uri;
+use rede_schema::{InputParam as SchemaIP, InputType as SchemaType};
+use regex::Regex;
 use serde::Deserialize;
 
 #[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(try_from = "InputParamFields")]
 pub(crate) struct InputParam {
     pub(crate) hint: Option<String>,
+    pub(crate) secret: bool,
+    pub(crate) kind: InputType,
+    pub(crate) choices: Vec<String>,
+    pub(crate) default: Option<String>,
+}
+
+/// Keys of the input param, validated to ensure that the regex pattern compiles and that the
+/// default value is one of the choices and valid for the type
+#[derive(Deserialize)]
+struct InputParamFields {
+    hint: Option<String>,
+    #[serde(default)]
+    secret: bool,
+    #[serde(rename = "type", default)]
+    kind: InputType,
+    #[serde(default)]
+    choices: Vec<String>,
+    default: Option<String>,
+}
+
+/// The types are given by name, `type = "int"`, except the regex one that must contain the
+/// pattern, `type.regex = "^[a-z]+$"`
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum InputType {
+    #[default]
+    String,
+    #[serde(alias = "integer")]
+    Int,
+    #[serde(alias = "number")]
+    Float,
+    #[serde(alias = "boolean")]
+    Bool,
+    Url,
+    Email,
+    Regex(String),
+}
+
+impl TryFrom<InputParamFields> for InputParam {
+    type Error = String;
+
+    fn try_from(fields: InputParamFields) -> Result<Self, Self::Error> {
+        if let InputType::Regex(pattern) = &fields.kind {
+            Regex::new(pattern).map_err(|e| format!("invalid regex `{pattern}`: {e}"))?;
+        }
+        if let Some(default) = &fields.default {
+            if !fields.choices.is_empty() && !fields.choices.contains(default) {
+                return Err(format!("the default `{default}` is not one of the choices"));
+            }
+            if !fields.kind.accepts(default) {
+                return Err(format!(
+                    "the default `{default}` is not valid for the type {}",
+                    fields.kind
+                ));
+            }
+        }
+        Ok(Self {
+            hint: fields.hint,
+            secret: fields.secret,
+            kind: fields.kind,
+            choices: fields.choices,
+            default: fields.default,
+        })
+    }
+}
+
+impl InputType {
+    /// Checks that the value is valid for the type, like the prompt does with the user input
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            InputType::String => true,
+            InputType::Int => value.parse::<i64>().is_ok(),
+            InputType::Float => value.parse::<f64>().is_ok(),
+            InputType::Bool => value.parse::<bool>().is_ok(),
+            InputType::Url => value
+                .parse::<Uri>()
+                .is_ok_and(|uri| uri.scheme().is_some() && uri.host().is_some()),
+            InputType::Email => value.split_once('@').is_some_and(|(user, domain)| {
+                !user.is_empty() && !domain.contains('@') && domain.contains('.')
+            }),
+            InputType::Regex(pattern) => {
+                Regex::new(&format!("^(?:{pattern})$")).is_ok_and(|regex| regex.is_match(value))
+            }
+        }
+    }
+}
+
+impl Display for InputType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputType::String => write!(f, "string"),
+            InputType::Int => write!(f, "int"),
+            InputType::Float => write!(f, "float"),
+            InputType::Bool => write!(f, "bool"),
+            InputType::Url => write!(f, "url"),
+            InputType::Email => write!(f, "email"),
+            InputType::Regex(pattern) => write!(f, "regex `{pattern}`"),
+        }
+    }
 }
 
 impl From<InputParam> for SchemaIP {
     fn from(value: InputParam) -> Self {
-        Self { hint: value.hint }
+        Self {
+            hint: value.hint,
+            secret: value.secret,
+            kind: value.kind.into(),
+            choices: value.choices,
+            default: value.default,
+        }
+    }
+}
+
+impl From<InputType> for SchemaType {
+    fn from(value: InputType) -> Self {
+        match value {
+            InputType::String => SchemaType::String,
+            InputType::Int => SchemaType::Int,
+            InputType::Float => SchemaType::Float,
+            InputType::Bool => SchemaType::Bool,
+            InputType::Url => SchemaType::Url,
+            InputType::Email => SchemaType::Email,
+            InputType::Regex(pattern) => SchemaType::Regex(pattern),
+        }
     }
 }
 
@@ -20,12 +144,20 @@ mod test {
     fn deserialize() {
         let toml = r#"
             hint = "hint"
+            secret = true
+            type = "int"
+            choices = ["1", "2"]
+            default = "2"
         "#;
         let input_param: InputParam = toml::from_str(toml).unwrap();
         assert_eq!(
             input_param,
             InputParam {
                 hint: Some("hint".to_string()),
+                secret: true,
+                kind: InputType::Int,
+                choices: vec!["1".to_string(), "2".to_string()],
+                default: Some("2".to_string()),
             }
         );
     }
@@ -35,4 +167,77 @@ mod test {
         let input_param: InputParam = toml::from_str("").unwrap();
         assert_eq!(input_param, InputParam::default());
     }
+
+    #[test]
+    fn deserialize_types() {
+        let kind = |toml: &str| toml::from_str::<InputParam>(toml).unwrap().kind;
+        assert_eq!(kind(r#"type = "boolean""#), InputType::Bool);
+        assert_eq!(kind(r#"type = "email""#), InputType::Email);
+        assert_eq!(
+            kind(r#"type.regex = "^v[0-9]+$""#),
+            InputType::Regex("^v[0-9]+$".to_string())
+        );
+        assert!(toml::from_str::<InputParam>(r#"type = "regex""#).is_err());
+        assert!(toml::from_str::<InputParam>(r#"type = "date""#).is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        let err = toml::from_str::<InputParam>(r#"type.regex = "v[0-9""#).unwrap_err();
+        assert!(err.message().starts_with("invalid regex `v[0-9`"));
+        let toml = r#"
+            choices = ["dev", "prod"]
+            default = "staging"
+        "#;
+        let err = toml::from_str::<InputParam>(toml).unwrap_err();
+        assert_eq!(
+            err.message(),
+            "the default `staging` is not one of the choices"
+        );
+    }
+
+    #[test]
+    fn default_of_type() {
+        let err = toml::from_str::<InputParam>(
+            r#"type = "int"
+default = "abc""#,
+        );
+        assert_eq!(
+            err.unwrap_err().message(),
+            "the default `abc` is not valid for the type int"
+        );
+        let invalid = [
+            r#"type = "float"
+default = "1,5""#,
+            r#"type = "bool"
+default = "yes""#,
+            r#"type = "url"
+default = "/relative""#,
+            r#"type = "email"
+default = "user""#,
+            r#"type.regex = "v[0-9]+"
+default = "version 1""#,
+        ];
+        for toml in invalid {
+            assert!(toml::from_str::<InputParam>(toml).is_err(), "{toml}");
+        }
+        let valid = [
+            r#"type = "int"
+default = "-1""#,
+            r#"type = "float"
+default = "1.5""#,
+            r#"type = "bool"
+default = "true""#,
+            r#"type = "url"
+default = "https://example.org/api""#,
+            r#"type = "email"
+default = "user@example.org""#,
+            r#"type.regex = "v[0-9]+"
+default = "v1""#,
+            r#"default = "anything""#,
+        ];
+        for toml in valid {
+            assert!(toml::from_str::<InputParam>(toml).is_ok(), "{toml}");
+        }
+    }
 }
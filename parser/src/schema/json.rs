@@ -0,0 +1,89 @@
+//! JSON bodies, written as a string or as a native TOML table or array
+
+use serde::Deserialize;
+use serde_json::{Map, Number, Value as JsonValue};
+use toml::Value as TomlValue;
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub(crate) enum Json {
+    Text(String),
+    Value(TomlValue),
+}
+
+/// Converts the TOML value into JSON, keeping the strings with placeholders as they are, so
+/// they are rendered later into the JSON document
+pub(crate) fn to_json(value: TomlValue) -> JsonValue {
+    match value {
+        TomlValue::String(string) => JsonValue::String(string),
+        TomlValue::Integer(int) => JsonValue::Number(int.into()),
+        TomlValue::Float(float) => {
+            Number::from_f64(float).map_or(JsonValue::Null, JsonValue::Number)
+        }
+        TomlValue::Boolean(bool) => JsonValue::Bool(bool),
+        TomlValue::Datetime(datetime) => JsonValue::String(datetime.to_string()),
+        TomlValue::Array(array) => JsonValue::Array(array.into_iter().map(to_json).collect()),
+        TomlValue::Table(table) => JsonValue::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, to_json(value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize)]
+    struct Parent {
+        json: Json,
+    }
+
+    fn parse(toml: &str) -> Json {
+        toml::from_str::<Parent>(toml).unwrap().json
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(
+            parse(r#"json = '{"id": 1}'"#),
+            Json::Text(r#"{"id": 1}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn table() {
+        let toml = r#"
+        [json]
+        name = "{{name}}"
+        count = "{{count:int}}"
+        date = 1979-05-27
+        tags = ["a", 1, 2.5, false]
+        nested = { id = 1 }
+        "#;
+        let Json::Value(value) = parse(toml) else {
+            panic!("the table is parsed as text");
+        };
+        assert_eq!(
+            to_json(value),
+            json!({
+                "name": "{{name}}",
+                "count": "{{count:int}}",
+                "date": "1979-05-27",
+                "tags": ["a", 1, 2.5, false],
+                "nested": { "id": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn array() {
+        let Json::Value(value) = parse(r#"json = [{ id = "{{id:int}}" }, { id = 2 }]"#) else {
+            panic!("the array is parsed as text");
+        };
+        assert_eq!(to_json(value), json!([{ "id": "{{id:int}}" }, { "id": 2 }]));
+    }
+}
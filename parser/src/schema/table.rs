@@ -1,7 +1,9 @@
 use std::collections::{btree_map, BTreeMap};
 use std::ops::Index;
 
-use crate::schema::body::FormDataValue;
+use crate::schema::asserts::HeaderAssert;
+use crate::schema::body::FormDataPart;
+use crate::schema::capture::Capture;
 use crate::schema::types::PrimitiveArray;
 use serde::Deserialize;
 
@@ -13,7 +15,9 @@ use super::input_param::InputParam;
 pub(crate) struct Table<V>(pub(crate) BTreeMap<String, V>);
 
 pub type PrimitiveTable = Table<PrimitiveArray>;
-pub type FormDataTable = Table<FormDataValue>;
+pub type FormDataTable = Table<FormDataPart>;
+pub type HeaderAssertsTable = Table<HeaderAssert>;
+pub type CapturesTable = Table<Capture>;
 
 #[cfg(feature = "input_params")]
 pub type InputParamsTable = Table<InputParam>;
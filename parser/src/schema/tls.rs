@@ -0,0 +1,158 @@
+use std::str::FromStr;
+
+use rede_schema::tls::{ClientCert, TlsVersion};
+use rede_schema::Tls as SchemaTls;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(try_from = "TlsFields")]
+pub(crate) struct Tls {
+    ca_cert: Option<String>,
+    client_cert: Option<Cert>,
+    min_version: Option<TlsVersion>,
+    insecure: bool,
+}
+
+/// Client certificate, given as PEM files with `cert` and `key` or as a PKCS#12 archive
+#[derive(Debug, PartialEq)]
+enum Cert {
+    Pem { cert: String, key: String },
+    Pkcs12 { path: String, password: String },
+}
+
+/// Keys accepted by the table, where the client certificate is either `cert` and `key` or
+/// `pkcs12` with its optional `password`
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TlsFields {
+    #[serde(alias = "cacert", alias = "ca", alias = "ca-cert")]
+    ca_cert: Option<String>,
+    cert: Option<String>,
+    key: Option<String>,
+    #[serde(alias = "p12", alias = "pfx")]
+    pkcs12: Option<String>,
+    password: Option<String>,
+    #[serde(
+        alias = "min-version",
+        alias = "min_tls_version",
+        default,
+        deserialize_with = "deserialize_version"
+    )]
+    min_version: Option<TlsVersion>,
+    #[serde(default)]
+    insecure: bool,
+}
+
+impl TryFrom<TlsFields> for Tls {
+    type Error = &'static str;
+
+    fn try_from(fields: TlsFields) -> Result<Self, Self::Error> {
+        let client_cert = match (fields.cert, fields.key, fields.pkcs12, fields.password) {
+            (None, None, None, None) => None,
+            (Some(cert), Some(key), None, None) => Some(Cert::Pem { cert, key }),
+            (None, None, Some(path), password) => Some(Cert::Pkcs12 {
+                path,
+                password: password.unwrap_or_default(),
+            }),
+            (Some(_), None, None, None) | (None, Some(_), None, None) => {
+                return Err("the PEM client certificate requires both `cert` and `key`")
+            }
+            _ => return Err("wanted either `cert` and `key` or `pkcs12` and `password`"),
+        };
+        Ok(Self {
+            ca_cert: fields.ca_cert,
+            client_cert,
+            min_version: fields.min_version,
+            insecure: fields.insecure,
+        })
+    }
+}
+
+fn deserialize_version<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<TlsVersion>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|version| TlsVersion::from_str(&version).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+impl From<Tls> for SchemaTls {
+    fn from(value: Tls) -> Self {
+        SchemaTls {
+            ca_cert: value.ca_cert,
+            client_cert: value.client_cert.map(|cert| match cert {
+                Cert::Pem { cert, key } => ClientCert::Pem { cert, key },
+                Cert::Pkcs12 { path, password } => ClientCert::Pkcs12 { path, password },
+            }),
+            min_version: value.min_version,
+            insecure: value.insecure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Parent {
+        tls: Tls,
+    }
+
+    fn parse(toml: &str) -> Result<SchemaTls, toml::de::Error> {
+        toml::from_str::<Parent>(toml).map(|parent| parent.tls.into())
+    }
+
+    #[test]
+    fn pem() {
+        let toml = r#"
+        [tls]
+        cacert = "certs/ca.pem"
+        cert = "certs/client.pem"
+        key = "certs/client.key"
+        min_version = "1.2"
+        "#;
+        let tls = parse(toml).unwrap();
+        assert_eq!(tls.ca_cert.as_deref(), Some("certs/ca.pem"));
+        assert_eq!(
+            tls.client_cert,
+            Some(ClientCert::Pem {
+                cert: "certs/client.pem".to_string(),
+                key: "certs/client.key".to_string()
+            })
+        );
+        assert_eq!(tls.min_version, Some(TlsVersion::Tls1_2));
+        assert!(!tls.insecure);
+    }
+
+    #[test]
+    fn pkcs12() {
+        let toml = r#"
+        [tls]
+        pkcs12 = "client.p12"
+        password = "{{password}}"
+        insecure = true
+        "#;
+        let tls = parse(toml).unwrap();
+        assert_eq!(
+            tls.client_cert,
+            Some(ClientCert::Pkcs12 {
+                path: "client.p12".to_string(),
+                password: "{{password}}".to_string()
+            })
+        );
+        assert!(tls.insecure);
+        assert_eq!(tls.ca_cert, None);
+    }
+
+    #[test]
+    fn invalid() {
+        let err = parse("tls = { cert = \"client.pem\" }").unwrap_err();
+        assert!(err.message().contains("requires both `cert` and `key`"));
+        let err = parse("tls = { cert = \"a.pem\", key = \"a.key\", pkcs12 = \"a.p12\" }");
+        assert!(err.unwrap_err().message().contains("wanted either"));
+        let err = parse("tls = { min_version = \"1.4\" }").unwrap_err();
+        assert!(err.message().contains("unknown TLS version 1.4"));
+        assert!(parse("tls = { unknown = true }").is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use std::ops::Range;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use miette::{miette, Result};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+/// Characters escaped by the `urlencode` filter, all but the unreserved ones of RFC 3986
+const URL_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Placeholder found in a text, like `{{host | default: "localhost"}}`. It's composed by the
+/// name of the placeholder to resolve and a chain of filters to apply to its value.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Expression<'a> {
+    /// Position of the whole placeholder in the text, braces included
+    pub span: Range<usize>,
+    pub name: &'a str,
+    pub filters: Vec<Filter<'a>>,
+}
+
+/// Transformation applied to the value of a placeholder
+#[derive(Debug, PartialEq)]
+pub(crate) enum Filter<'a> {
+    /// Value to use if the placeholder is unresolved
+    Default(String),
+    Upper,
+    Lower,
+    UrlEncode,
+    Base64,
+    /// Unknown filter or with wrong arguments, it fails when applied
+    Invalid(&'a str),
+}
+
+impl Expression<'_> {
+    /// Applies in order the filters to the value of the placeholder
+    pub fn evaluate(&self, value: Option<String>) -> Result<Option<String>> {
+        self.filters
+            .iter()
+            .try_fold(value, |value, filter| filter.apply(value))
+    }
+
+    /// Returns true if the expression always has a value thanks to a `default` filter
+    pub fn has_default(&self) -> bool {
+        self.filters
+            .iter()
+            .any(|filter| matches!(filter, Filter::Default(_)))
+    }
+}
+
+impl Filter<'_> {
+    fn apply(&self, value: Option<String>) -> Result<Option<String>> {
+        Ok(match self {
+            Filter::Default(default) => value.or_else(|| Some(default.clone())),
+            Filter::Upper => value.map(|v| v.to_uppercase()),
+            Filter::Lower => value.map(|v| v.to_lowercase()),
+            Filter::UrlEncode => value.map(|v| utf8_percent_encode(&v, URL_ENCODE_SET).to_string()),
+            Filter::Base64 => value.map(|v| STANDARD.encode(v)),
+            Filter::Invalid(filter) => {
+                return Err(miette!(
+                code = "invalid placeholder filter",
+                url = "https://rede.sotoestevez.dev/reference_guide/request_dsl/placeholders.html",
+                help = "the available filters are default, upper, lower, urlencode and base64",
+                "The placeholder filter `{filter}` is not valid"
+            ))
+            }
+        })
+    }
+}
+
+/// Finds all the placeholders in the text. Any pair of double braces not containing a valid
+/// placeholder name is ignored, as it's considered part of the text.
+pub(crate) fn parse(text: &str) -> Vec<Expression<'_>> {
+    let mut expressions = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|i| i + offset) {
+        if let Some(expression) = parse_at(text, start) {
+            offset = expression.span.end;
+            expressions.push(expression);
+        } else {
+            offset = start + 1;
+        }
+    }
+    expressions
+}
+
+/// Replaces every placeholder in the text with the value of its name, provided by `value_of`,
+/// after applying its filters. The placeholders without value are kept as they are. Along with
+/// the rendered text, returns if all the placeholders were replaced.
+pub(crate) fn render(
+    text: &str,
+    mut value_of: impl FnMut(&str) -> Result<Option<String>>,
+) -> Result<(String, bool)> {
+    let mut rendered = String::with_capacity(text.len());
+    let mut complete = true;
+    let mut last = 0;
+    for expression in parse(text) {
+        rendered.push_str(&text[last..expression.span.start]);
+        if let Some(value) = expression.evaluate(value_of(expression.name)?)? {
+            rendered.push_str(&value);
+        } else {
+            rendered.push_str(&text[expression.span.clone()]);
+            complete = false;
+        }
+        last = expression.span.end;
+    }
+    rendered.push_str(&text[last..]);
+    Ok((rendered, complete))
+}
+
+fn parse_at(text: &str, start: usize) -> Option<Expression<'_>> {
+    let content_start = start + 2;
+    let content = &text[content_start..];
+    let end = content_start + closing(content)?;
+    let mut segments = split_filters(&text[content_start..end]).into_iter();
+    let name = segments.next()?.trim();
+    is_valid_name(name).then(|| Expression {
+        span: start..end + 2,
+        name,
+        filters: segments.map(parse_filter).collect(),
+    })
+}
+
+/// Finds the position of the closing braces, ignoring those inside quoted filter arguments
+fn closing(content: &str) -> Option<usize> {
+    let mut in_filters = false;
+    let mut quote = None;
+    let mut chars = content.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match quote {
+            Some(_) if c == '\\' => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '|' => in_filters = true,
+                '"' | '\'' if in_filters => quote = Some(c),
+                '}' if content[i..].starts_with("}}") => return Some(i),
+                '{' if content[i..].starts_with("{{") => return None,
+                _ => {}
+            },
+        }
+    }
+    None
+}
+
+/// Splits the content of the placeholder by the pipes outside quoted filter arguments
+fn split_filters(content: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in content.char_indices() {
+        match quote {
+            _ if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '|' => {
+                    segments.push(&content[start..i]);
+                    start = i + 1;
+                }
+                '"' | '\'' if !segments.is_empty() => quote = Some(c),
+                _ => {}
+            },
+        }
+    }
+    segments.push(&content[start..]);
+    segments
+}
+
+/// Valid names are those made of alphanumerics, `-`, `_` and `.`, or the dynamic placeholders:
+/// a `$` followed by the function name and its arguments, like `$randomInt 1 100`
+fn is_valid_name(name: &str) -> bool {
+    if let Some(function) = name.strip_prefix('$') {
+        let (function, args) = function.split_once(' ').unwrap_or((function, ""));
+        !function.is_empty()
+            && function.chars().all(|c| c.is_ascii_alphanumeric())
+            && !args.contains(['{', '}'])
+    } else {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }
+}
+
+fn parse_filter(segment: &str) -> Filter<'_> {
+    let filter = segment.trim();
+    let (name, arg) = match filter.split_once(':') {
+        Some((name, arg)) => (name.trim_end(), Some(arg.trim_start())),
+        None => (filter, None),
+    };
+    match (name, arg) {
+        ("default", Some(arg)) => unquote(arg).map_or(Filter::Invalid(filter), Filter::Default),
+        ("upper", None) => Filter::Upper,
+        ("lower", None) => Filter::Lower,
+        ("urlencode", None) => Filter::UrlEncode,
+        ("base64", None) => Filter::Base64,
+        _ => Filter::Invalid(filter),
+    }
+}
+
+/// Returns the value of the argument, removing the quotes and escapes if it's quoted
+fn unquote(arg: &str) -> Option<String> {
+    let Some(quote) = arg.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+        return Some(arg.to_string());
+    };
+    let mut value = String::new();
+    let mut chars = arg[1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?),
+            c if c == quote => return chars.as_str().is_empty().then_some(value),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_expressions() {
+        let text = "{{host}}/{{ id }}/{{$randomInt 1 10}}/{{not valid}}/{{}}/{{{name | upper}}}";
+        let expressions = parse(text);
+        let names: Vec<&str> = expressions.iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["host", "id", "$randomInt 1 10", "name"]);
+        assert_eq!(&text[expressions[0].span.clone()], "{{host}}");
+        assert_eq!(&text[expressions[3].span.clone()], "{{name | upper}}");
+        assert_eq!(expressions[3].filters, vec![Filter::Upper]);
+    }
+
+    #[test]
+    fn parse_filters() {
+        let text = r#"{{host | default: "local|host}}" | upper | urlencode | base64 | lower}}"#;
+        let expression = &parse(text)[0];
+        assert_eq!(expression.span, 0..text.len());
+        assert_eq!(
+            expression.filters,
+            vec![
+                Filter::Default("local|host}}".to_string()),
+                Filter::Upper,
+                Filter::UrlEncode,
+                Filter::Base64,
+                Filter::Lower
+            ]
+        );
+
+        let filters = |text| parse(text).remove(0).filters;
+        assert_eq!(
+            filters(r"{{a | default: 'it\'s'}}"),
+            vec![Filter::Default("it's".to_string())]
+        );
+        assert_eq!(
+            filters("{{a | default: 8080}}"),
+            vec![Filter::Default("8080".to_string())]
+        );
+        assert_eq!(filters("{{a | uper}}"), vec![Filter::Invalid("uper")]);
+        assert_eq!(filters("{{a | default}}"), vec![Filter::Invalid("default")]);
+        assert_eq!(
+            filters("{{a | upper: 1}}"),
+            vec![Filter::Invalid("upper: 1")]
+        );
+        assert_eq!(
+            filters(r#"{{a | default: "x" y}}"#),
+            vec![Filter::Invalid(r#"default: "x" y"#)]
+        );
+        assert!(parse(r#"{{a | default: "unclosed}}"#).is_empty());
+    }
+
+    #[test]
+    fn evaluate() {
+        let evaluate =
+            |text, value: Option<&str>| parse(text)[0].evaluate(value.map(str::to_string)).unwrap();
+        assert_eq!(evaluate("{{a | upper}}", Some("Abc")).unwrap(), "ABC");
+        assert_eq!(evaluate("{{a | lower}}", Some("Abc")).unwrap(), "abc");
+        assert_eq!(
+            evaluate("{{a | urlencode}}", Some("a b&c=d/é~")).unwrap(),
+            "a%20b%26c%3Dd%2F%C3%A9~"
+        );
+        assert_eq!(
+            evaluate("{{a | base64}}", Some("user:pass")).unwrap(),
+            "dXNlcjpwYXNz"
+        );
+        assert_eq!(evaluate("{{a | upper}}", None), None);
+        assert_eq!(
+            evaluate(r#"{{a | default: "b" | upper}}"#, None).unwrap(),
+            "B"
+        );
+        assert_eq!(
+            evaluate(r#"{{a | default: "b" | upper}}"#, Some("a")).unwrap(),
+            "A"
+        );
+        assert!(parse("{{a | uper}}")[0].evaluate(None).is_err());
+    }
+
+    #[test]
+    fn render_text() {
+        let value_of = |name: &str| Ok((name == "a").then(|| "value".to_string()));
+        let (text, complete) =
+            render(r#"{{a}}-{{ a | upper }}-{{b | default: "x"}}"#, value_of).unwrap();
+        assert_eq!(text, "value-VALUE-x");
+        assert!(complete);
+
+        let (text, complete) = render("{{a}}/{{b | upper}}", value_of).unwrap();
+        assert_eq!(text, "value/{{b | upper}}");
+        assert!(!complete);
+    }
+}
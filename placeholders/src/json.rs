@@ -0,0 +1,208 @@
+//! Placeholders of the JSON documents, rendered into their keys and strings. The strings made
+//! only of a typed placeholder, like `"{{count:int}}"`, are replaced by a value of the type.
+
+use std::borrow::Cow;
+use std::mem::take;
+
+use miette::{miette, Result};
+use serde_json::{Number, Value};
+
+/// Types that can be given to a placeholder filling a whole string, like `{{count:int}}`
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Type {
+    Int,
+    Float,
+    Bool,
+}
+
+/// Returns the texts of the document that can contain placeholders: the keys of its objects and
+/// its strings, the typed placeholders without their type
+pub(crate) fn texts(value: &Value) -> Vec<Cow<'_, str>> {
+    match value {
+        Value::String(string) => {
+            vec![typed(string).map_or(Cow::Borrowed(string), |(ph, _)| Cow::Owned(ph))]
+        }
+        Value::Array(array) => array.iter().flat_map(texts).collect(),
+        Value::Object(object) => object
+            .iter()
+            .flat_map(|(key, value)| {
+                let mut texts = texts(value);
+                texts.push(Cow::Borrowed(key));
+                texts
+            })
+            .collect(),
+        Value::Null | Value::Bool(_) | Value::Number(_) => Vec::new(),
+    }
+}
+
+/// Renders in place the keys and strings of the document with `render_text`, which returns the
+/// rendered text and if all its placeholders were resolved. The typed placeholders are converted
+/// to their type once resolved, otherwise they are kept as they are.
+///
+/// # Errors
+///
+/// Fails if rendering any text fails or if the value of a typed placeholder is not valid for
+/// its type
+pub(crate) fn render(
+    value: &mut Value,
+    render_text: &impl Fn(&str) -> Result<(String, bool)>,
+) -> Result<()> {
+    match value {
+        Value::String(string) => {
+            if let Some((placeholder, kind)) = typed(string) {
+                let (rendered, complete) = render_text(&placeholder)?;
+                if complete {
+                    let typed = kind.convert(&rendered).ok_or_else(|| {
+                        miette!(
+                            code = "invalid typed placeholder",
+                            url = "https://rede.sotoestevez.dev/reference_guide/request_dsl/body.html#json-tables",
+                            help = format!("the value given to the placeholder must be {}", kind.expected()),
+                            "The value of the placeholder `{string}` is not valid for its type"
+                        )
+                    })?;
+                    *value = typed;
+                }
+            } else {
+                *string = render_text(string)?.0;
+            }
+        }
+        Value::Array(array) => {
+            for value in array {
+                render(value, render_text)?;
+            }
+        }
+        Value::Object(object) => {
+            for (key, mut value) in take(object) {
+                render(&mut value, render_text)?;
+                object.insert(render_text(&key)?.0, value);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+    Ok(())
+}
+
+/// Returns the placeholder without its type, along with the type, if the text is only a typed
+/// placeholder
+fn typed(text: &str) -> Option<(String, Type)> {
+    let content = text.strip_prefix("{{")?.strip_suffix("}}")?;
+    let (expression, kind) = content.rsplit_once(':')?;
+    let kind = match kind.trim() {
+        "int" => Type::Int,
+        "float" => Type::Float,
+        "bool" => Type::Bool,
+        _ => return None,
+    };
+    Some((format!("{{{{{}}}}}", expression.trim_end()), kind))
+}
+
+impl Type {
+    fn convert(self, value: &str) -> Option<Value> {
+        let value = value.trim();
+        match self {
+            Type::Int => value.parse::<i64>().ok().map(Value::from),
+            Type::Float => value
+                .parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number),
+            Type::Bool => value.parse::<bool>().ok().map(Value::Bool),
+        }
+    }
+
+    fn expected(self) -> &'static str {
+        match self {
+            Type::Int => "an integer",
+            Type::Float => "a number",
+            Type::Bool => "true or false",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn render_with(value: &mut Value, values: &[(&str, &str)]) -> Result<()> {
+        render(value, &|text| {
+            let mut rendered = text.to_string();
+            for (key, value) in values {
+                rendered = rendered.replace(&format!("{{{{{key}}}}}"), value);
+            }
+            let complete = !rendered.contains("{{");
+            Ok((rendered, complete))
+        })
+    }
+
+    #[test]
+    fn typed_placeholders() {
+        assert_eq!(
+            typed("{{count:int}}"),
+            Some(("{{count}}".to_string(), Type::Int))
+        );
+        assert_eq!(
+            typed("{{price : float}}"),
+            Some(("{{price}}".to_string(), Type::Float))
+        );
+        assert_eq!(
+            typed("{{enabled | default: 'true' :bool}}"),
+            Some(("{{enabled | default: 'true'}}".to_string(), Type::Bool))
+        );
+        assert_eq!(typed("{{time:date}}"), None);
+        assert_eq!(typed("id {{count:int}}"), None);
+    }
+
+    #[test]
+    fn texts_of_document() {
+        let value = json!({ "{{key}}": ["{{a}}", 1, { "b": "{{b:int}}" }] });
+        let mut texts = texts(&value);
+        texts.sort();
+        assert_eq!(texts, vec!["b", "{{a}}", "{{b}}", "{{key}}"]);
+    }
+
+    #[test]
+    fn render_document() {
+        let mut value = json!({
+            "name": "{{name}}",
+            "count": "{{count:int}}",
+            "price": "{{price:float}}",
+            "enabled": "{{enabled:bool}}",
+            "{{key}}": ["{{name}}", 1],
+            "missing": "{{missing:int}}",
+        });
+        let values = [
+            ("name", r#"x"y"#),
+            ("count", "3"),
+            ("price", "9.5"),
+            ("enabled", "true"),
+            ("key", "tags"),
+        ];
+        render_with(&mut value, &values).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": r#"x"y"#,
+                "count": 3,
+                "price": 9.5,
+                "enabled": true,
+                "tags": [r#"x"y"#, 1],
+                "missing": "{{missing:int}}",
+            })
+        );
+        assert_eq!(value["name"].to_string(), r#""x\"y""#);
+    }
+
+    #[test]
+    fn invalid_typed_value() {
+        let mut value = json!({ "count": "{{count:int}}" });
+        let err = render_with(&mut value, &[("count", "abc")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The value of the placeholder `{{count:int}}` is not valid for its type"
+        );
+        let mut value = json!(["{{enabled:bool}}"]);
+        assert!(render_with(&mut value, &[("enabled", "yes")]).is_err());
+    }
+}
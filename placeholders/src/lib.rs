@@ -26,7 +26,7 @@
 //!     let resolver = Resolver::new()
 //!         .add_picker(Box::new(EnvVarPicker))
 //!         .add_picker(Box::new(VariablesPicker::new(&request.variables)));
-//!     resolver.resolve(&placeholders)
+//!     resolver.resolve(&placeholders)?
 //! };
 //! // render new request
 //! let renderer = Renderer::new(&placeholders, ph_values);
@@ -40,6 +40,8 @@
 
 #![warn(clippy::pedantic)]
 
+mod expression;
+mod json;
 mod placeholders;
 mod renderer;
 mod resolver;
@@ -1,63 +1,106 @@
 use std::collections::{BTreeMap, HashSet};
 
 use http::HeaderName;
-use regex::Regex;
 
 use rede_schema::body::FormDataValue;
 use rede_schema::{Body, Request};
 
+use crate::expression::{parse, Expression};
+use crate::json;
+
 type PlaceholdersMap = BTreeMap<String, HashSet<Location>>;
 
 /// The `Placeholders` struct analyzes a request and extracts all the placeholders from its parts.
 #[derive(Debug, Default)]
-pub struct Placeholders(PlaceholdersMap);
-// todo possible improvement: support placeholders on Header, QueryParms and Form keys
+pub struct Placeholders {
+    map: PlaceholdersMap,
+    /// Placeholders with at least one occurrence without a default value
+    required: HashSet<String>,
+}
 
 impl From<&Request> for Placeholders {
     fn from(request: &Request) -> Self {
-        let re = Regex::new(r"\{\{([A-z0-9-_.]*)}}").unwrap();
-
         let mut placeholder_map = Self::new();
-        let set = find_placeholders(&re, &request.url);
+        let set = parse(&request.url);
         placeholder_map.add_all(&Location::Url, set);
 
         for (n, v) in &request.headers {
-            let set = find_placeholders(&re, v.to_str().unwrap());
+            let set = parse(v.to_str().unwrap());
             placeholder_map.add_all(&Location::Headers(n.to_owned()), set);
         }
 
+        for (n, v) in &request.templated_headers {
+            let location = Location::HeaderKey(n.clone());
+            placeholder_map.add_all(&location, parse(n));
+            placeholder_map.add_all(&location, parse(v));
+        }
+
         for (k, v) in &request.query_params {
-            let set = find_placeholders(&re, v.as_str());
+            let set = parse(v.as_str());
             placeholder_map.add_all(&Location::QueryParams(k.clone()), set);
+            let set = parse(k.as_str());
+            placeholder_map.add_all(&Location::QueryParamKey(k.clone()), set);
         }
 
         match &request.body {
             Body::Raw { content, .. } => {
-                let set = find_placeholders(&re, content);
+                let set = parse(content);
                 placeholder_map.add_all(&Location::Body, set);
             }
+            Body::Json(value) => {
+                for text in json::texts(value) {
+                    placeholder_map.add_all(&Location::Body, parse(&text));
+                }
+            }
             Body::Binary { path, .. } => {
-                let set = find_placeholders(&re, path);
+                let set = parse(path);
                 placeholder_map.add_all(&Location::Body, set);
             }
             Body::XFormUrlEncoded(form) => {
                 for (k, v) in form {
-                    let set = find_placeholders(&re, v);
+                    let set = parse(v);
                     placeholder_map.add_all(&Location::BodyForm(k.clone()), set);
+                    let set = parse(k);
+                    placeholder_map.add_all(&Location::BodyFormKey(k.clone()), set);
                 }
             }
             Body::FormData(form) => {
-                for (k, v) in form {
-                    let content = match v {
+                for (k, part) in form {
+                    let content = match &part.value {
                         FormDataValue::Text(v) | FormDataValue::File(v) => v,
                     };
-                    let set = find_placeholders(&re, content);
-                    placeholder_map.add_all(&Location::BodyForm(k.clone()), set);
+                    for text in Some(content).into_iter().chain(&part.filename) {
+                        placeholder_map.add_all(&Location::BodyForm(k.clone()), parse(text));
+                    }
+                    let set = parse(k);
+                    placeholder_map.add_all(&Location::BodyFormKey(k.clone()), set);
+                }
+            }
+            Body::GraphQL(graphql) => {
+                placeholder_map.add_all(&Location::Body, parse(&graphql.query));
+                let variables = graphql.variables.iter().flat_map(json::texts);
+                for text in variables.chain(graphql.operation_name.as_deref().map(Into::into)) {
+                    placeholder_map.add_all(&Location::Body, parse(&text));
                 }
             }
             Body::None => {}
         }
 
+        for value in request.auth.values() {
+            let set = parse(value);
+            placeholder_map.add_all(&Location::Auth, set);
+        }
+
+        for value in request.cookies.values() {
+            let set = parse(value);
+            placeholder_map.add_all(&Location::Cookies, set);
+        }
+
+        for value in request.tls.values() {
+            let set = parse(value);
+            placeholder_map.add_all(&Location::Tls, set);
+        }
+
         placeholder_map
     }
 }
@@ -69,7 +112,14 @@ impl Placeholders {
 
     /// Returns an iterator with the keys of the request's placeholders.
     pub fn keys(&self) -> impl Iterator<Item = &str> {
-        self.0.keys().map(String::as_str)
+        self.map.keys().map(String::as_str)
+    }
+
+    /// Returns true if every occurrence of the placeholder has a default value, like
+    /// `{{host | default: "localhost"}}`, so the placeholder doesn't need to be resolved.
+    #[must_use]
+    pub fn has_default(&self, key: &str) -> bool {
+        self.map.contains_key(key) && !self.required.contains(key)
     }
 
     /// Returns an iterator over the placeholders and their locations in the request
@@ -79,29 +129,32 @@ impl Placeholders {
     }
 
     fn insert(&mut self, key: &str, location: Location) {
-        if let Some(locations) = self.0.get_mut(key) {
+        if let Some(locations) = self.map.get_mut(key) {
             locations.insert(location);
         } else {
             #[allow(clippy::mutable_key_type)]
             let mut set = HashSet::new();
             set.insert(location);
-            self.0.insert(key.to_string(), set);
+            self.map.insert(key.to_string(), set);
         }
     }
 
     pub(crate) fn add_all<'a>(
         &mut self,
         location: &Location,
-        keys: impl IntoIterator<Item = &'a str>,
+        expressions: impl IntoIterator<Item = Expression<'a>>,
     ) {
-        for key in keys {
-            self.insert(key, location.clone());
+        for expression in expressions {
+            if !expression.has_default() {
+                self.required.insert(expression.name.to_string());
+            }
+            self.insert(expression.name, location.clone());
         }
     }
 
     #[cfg(test)]
     pub(self) fn len(&self) -> usize {
-        self.0.len()
+        self.map.len()
     }
 }
 
@@ -110,33 +163,33 @@ impl<'p> IntoIterator for &'p Placeholders {
     type IntoIter = <&'p PlaceholdersMap as IntoIterator>::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.map.iter()
     }
 }
 
-fn find_placeholders<'a>(regex: &Regex, haystack: &'a str) -> Vec<&'a str> {
-    regex
-        .find_iter(haystack)
-        .map(|c| &c.as_str()[2..c.len() - 2])
-        .collect()
-}
-
 /// Represents the part of the request where a placeholder can be present
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub enum Location {
     Url,
     Headers(HeaderName),
+    /// Name or value of a header whose name contains placeholders
+    HeaderKey(String),
     QueryParams(String),
+    /// Key of a query param
+    QueryParamKey(String),
     Body,
     BodyForm(String),
+    /// Key of a field of a form body
+    BodyFormKey(String),
+    Auth,
+    Cookies,
+    Tls,
 }
 
 #[cfg(test)]
 mod test {
-    use std::collections::HashSet;
-
     use http::{HeaderMap, Method, Version};
-    use rede_schema::Body;
+    use rede_schema::{Asserts, Auth, Body, Tls};
 
     use super::*;
 
@@ -149,22 +202,26 @@ mod test {
         pm.insert("id", Location::Body);
 
         assert_eq!(pm.len(), 2);
-        assert_eq!(pm.0["host"].len(), 1);
-        assert_eq!(pm.0["id"].len(), 2);
+        assert_eq!(pm.map["host"].len(), 1);
+        assert_eq!(pm.map["id"].len(), 2);
     }
 
     #[test]
     fn add_all() {
         let mut pm = Placeholders::new();
         pm.insert("two", Location::Url);
-        let mut set = HashSet::new();
-        set.insert("one");
-        set.insert("two");
-        pm.add_all(&Location::Headers("Header".parse().unwrap()), set);
+        let expressions = parse(r#"{{one | default: "1"}} {{two}}"#);
+        pm.add_all(&Location::Headers("Header".parse().unwrap()), expressions);
 
         assert_eq!(pm.len(), 2);
-        assert_eq!(pm.0["one"].len(), 1);
-        assert_eq!(pm.0["two"].len(), 2);
+        assert_eq!(pm.map["one"].len(), 1);
+        assert_eq!(pm.map["two"].len(), 2);
+        assert!(pm.has_default("one"));
+        assert!(!pm.has_default("two"));
+        assert!(!pm.has_default("three"));
+
+        pm.add_all(&Location::Url, parse("{{one | upper}}"));
+        assert!(!pm.has_default("one"));
     }
 
     #[test]
@@ -177,6 +234,7 @@ mod test {
         let query_params = vec![
             ("genre".to_string(), "{{genre}}".to_string()),
             ("release".to_string(), "before:{{date}}".to_string()),
+            ("{{filter}}".to_string(), "{{genre}}".to_string()),
         ];
 
         let request = Request {
@@ -185,7 +243,9 @@ mod test {
             http_version: Version::HTTP_11,
             metadata: BTreeMap::default(),
             headers,
+            templated_headers: vec![("{{tenant}}-Id".to_string(), "{{id}}".to_string())],
             query_params,
+            cookies: BTreeMap::from([("session".to_string(), "{{session}}".to_string())]),
             variables: BTreeMap::new(),
             body: Body::Raw {
                 content: r#"
@@ -200,26 +260,62 @@ mod test {
                 .to_string(),
                 mime: mime::APPLICATION_JSON,
             },
+            compression: None,
+            decompress: None,
+            auth: Auth::Bearer {
+                token: "{{token}}".to_string(),
+            },
+            tls: Tls {
+                ca_cert: Some("{{certs}}/ca.pem".to_string()),
+                ..Default::default()
+            },
+            asserts: Asserts::default(),
+            captures: BTreeMap::new(),
 
             #[cfg(feature = "input_params")]
             input_params: BTreeMap::new(),
         };
 
         let placeholders = Placeholders::from(&request);
-        assert_eq!(placeholders.len(), 5);
-        assert_eq!(placeholders.0["host"].len(), 2);
-        assert_eq!(placeholders.0["name"].len(), 1);
-        assert_eq!(placeholders.0["genre"].len(), 2);
-        assert_eq!(placeholders.0["location"].len(), 1);
-        assert_eq!(placeholders.0["date"].len(), 1);
+        assert_eq!(placeholders.len(), 11);
+        assert_eq!(placeholders.map["host"].len(), 2);
+        assert_eq!(placeholders.map["name"].len(), 1);
+        assert_eq!(placeholders.map["genre"].len(), 3);
+        assert_eq!(placeholders.map["location"].len(), 1);
+        assert_eq!(placeholders.map["date"].len(), 1);
+        assert_eq!(placeholders.map["token"].len(), 1);
 
         assert_eq!(
-            placeholders.0["location"].iter().next().unwrap(),
+            placeholders.map["location"].iter().next().unwrap(),
             &Location::Headers("Location".parse().unwrap())
         );
         assert_eq!(
-            placeholders.0["date"].iter().next().unwrap(),
+            placeholders.map["date"].iter().next().unwrap(),
             &Location::QueryParams("release".to_string())
         );
+        assert_eq!(
+            placeholders.map["token"].iter().next().unwrap(),
+            &Location::Auth
+        );
+        assert_eq!(
+            placeholders.map["session"].iter().next().unwrap(),
+            &Location::Cookies
+        );
+        assert_eq!(
+            placeholders.map["certs"].iter().next().unwrap(),
+            &Location::Tls
+        );
+        assert_eq!(
+            placeholders.map["tenant"].iter().next().unwrap(),
+            &Location::HeaderKey("{{tenant}}-Id".to_string())
+        );
+        assert_eq!(
+            placeholders.map["id"].iter().next().unwrap(),
+            &Location::HeaderKey("{{tenant}}-Id".to_string())
+        );
+        assert_eq!(
+            placeholders.map["filter"].iter().next().unwrap(),
+            &Location::QueryParamKey("{{filter}}".to_string())
+        );
     }
 }
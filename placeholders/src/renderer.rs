@@ -1,10 +1,15 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::mem::take;
+use std::str::FromStr;
 
-use http::{HeaderMap, HeaderName};
+use http::header::Entry;
+use http::{HeaderMap, HeaderName, HeaderValue};
 use miette::{miette, Result};
 use rede_schema::body::FormDataValue;
 use rede_schema::{Body, Request};
 
+use crate::expression;
+use crate::json;
 use crate::placeholders::Location;
 use crate::resolver::PlaceholderValues;
 use crate::Placeholders;
@@ -17,13 +22,6 @@ pub struct Renderer<'ph> {
     values: PlaceholderValues<'ph>,
 }
 
-macro_rules! replace_pointer {
-    ($pointer:expr, $placeholder:expr, $value:expr) => {
-        let new_value = $pointer.replace($placeholder, $value);
-        *$pointer = new_value;
-    };
-}
-
 impl<'ph> Renderer<'ph> {
     /// Creates a new instance of a `Renderer` that will be able to render request using the given
     /// placeholders and values.
@@ -41,8 +39,8 @@ impl<'ph> Renderer<'ph> {
     ///
     /// In case that any of the placeholder keys is unresolved the render operation won't fail.
     /// It will continue without replacing the placeholder. This is by design to allow the option
-    /// of chaining multiple renderings to make multistep replacements that could help to, for
-    /// example, enable in the future the option for nested placeholders.
+    /// of chaining multiple renderings to make multistep replacements. Nested placeholders, those
+    /// inside the values of other placeholders, are already replaced by the [`Resolver`](crate::Resolver).
     ///
     /// # Errors
     ///
@@ -53,52 +51,86 @@ impl<'ph> Renderer<'ph> {
         let mut headers = request.headers;
         let mut query_params = request.query_params;
         let mut body = request.body;
+        let mut auth = request.auth;
+        let mut cookies = request.cookies;
+        let mut tls = request.tls;
 
-        for (key, locations) in self.placeholders {
-            let val = self.values.get_value(key); // todo this could be changed into a map operation
-            if let Some(val) = val {
-                let placeholder = format!("{{{{{key}}}}}");
-                for location in locations {
-                    match location {
-                        Location::Url => url = url.replace(&placeholder, val),
-                        Location::Headers(name) => {
-                            render_headers(&mut headers, name, &placeholder, val)?;
-                        }
-                        Location::QueryParams(key) => {
-                            if let Some((_, v)) = query_params.iter_mut().find(|(k, _)| k == key) {
-                                replace_pointer!(v, &placeholder, val);
+        #[allow(clippy::mutable_key_type)]
+        let locations: HashSet<&Location> = self
+            .placeholders
+            .iter()
+            .flat_map(|(_, locations)| locations)
+            .collect();
+        for location in locations {
+            match location {
+                Location::Url => url = self.render_text(&url)?,
+                Location::Headers(name) => self.render_headers(&mut headers, name)?,
+                Location::QueryParams(key) => {
+                    for (_, v) in query_params.iter_mut().filter(|(k, _)| k == key) {
+                        *v = self.render_text(v)?;
+                    }
+                }
+                Location::BodyForm(key) => match &mut body {
+                    Body::FormData(form) => {
+                        if let Some(part) = form.get_mut(key) {
+                            let (FormDataValue::Text(v) | FormDataValue::File(v)) = &mut part.value;
+                            *v = self.render_text(v)?;
+                            if let Some(filename) = &mut part.filename {
+                                *filename = self.render_text(filename)?;
                             }
                         }
-                        Location::BodyForm(k) => match &mut body {
-                            Body::FormData(form) => {
-                                render_form_data(form, k, &placeholder, val);
-                            }
-                            Body::XFormUrlEncoded(form) => {
-                                render_form_urlencoded(form, k, &placeholder, val);
-                            }
-                            _ => {}
-                        },
-                        Location::Body => {
-                            if let Body::Raw { content, .. } | Body::Binary { path: content, .. } =
-                                &mut body
-                            {
-                                replace_pointer!(content, &placeholder, val);
-                            }
+                    }
+                    Body::XFormUrlEncoded(form) => {
+                        if let Some(v) = form.get_mut(key) {
+                            *v = self.render_text(v)?;
                         }
                     }
-                }
+                    _ => {}
+                },
+                Location::Body => self.render_body(&mut body)?,
+                Location::Auth => self.render_values(auth.values_mut())?,
+                Location::Cookies => self.render_values(cookies.values_mut())?,
+                Location::Tls => self.render_values(tls.values_mut())?,
+                // the keys are renamed once all the values are rendered, as the
+                // locations of the values are identified by their keys
+                Location::HeaderKey(_) | Location::QueryParamKey(_) | Location::BodyFormKey(_) => {}
             }
         }
 
+        for (name, value) in request.templated_headers {
+            let name = self.render_text(&name)?;
+            let header = HeaderName::from_str(&name)
+                .map_err(|_| miette!("rendered name of header {name} is invalid"))?;
+            let value = HeaderValue::from_str(&self.render_text(&value)?)
+                .map_err(|_| miette!("rendered value of header {name} is invalid"))?;
+            headers.append(header, value);
+        }
+        for (key, _) in &mut query_params {
+            *key = self.render_text(key)?;
+        }
+        match &mut body {
+            Body::FormData(form) => *form = self.render_keys(take(form))?,
+            Body::XFormUrlEncoded(form) => *form = self.render_keys(take(form))?,
+            _ => {}
+        }
+
         Ok(Request {
             method: request.method,
             url,
             http_version: request.http_version,
             metadata: request.metadata,
             headers,
+            templated_headers: Vec::new(),
             query_params,
+            cookies,
             variables: request.variables,
             body,
+            compression: request.compression,
+            decompress: request.decompress,
+            auth,
+            tls,
+            asserts: request.asserts,
+            captures: request.captures,
 
             #[cfg(feature = "input_params")]
             input_params: request.input_params,
@@ -106,43 +138,68 @@ impl<'ph> Renderer<'ph> {
     }
 }
 
-fn render_headers(
-    header_map: &mut HeaderMap,
-    header: &HeaderName,
-    placeholder: &str,
-    val: &str,
-) -> Result<()> {
-    if let Some(header_value) = header_map.get_mut(header) {
-        let new_value = header_value
-            .to_str()
-            .map_err(|_| miette!("failed to convert value of header {header} to string"))?;
-        let new_value = new_value.to_string().replace(placeholder, val);
-        *header_value = new_value
-            .parse()
-            .map_err(|_| miette!("rendered value of header {header} is invalid"))?;
+impl Renderer<'_> {
+    /// Replaces all the resolved placeholders present in the text, applying their filters
+    fn render_text(&self, text: &str) -> Result<String> {
+        self.render_complete(text).map(|(text, _)| text)
     }
-    Ok(())
-}
 
-fn render_form_data(
-    form: &mut BTreeMap<String, FormDataValue>,
-    key: &str,
-    placeholder: &str,
-    val: &str,
-) {
-    if let Some(FormDataValue::Text(v) | FormDataValue::File(v)) = form.get_mut(key) {
-        replace_pointer!(v, placeholder, val);
+    /// Renders the text like [`render_text`](Self::render_text), returning along with the
+    /// rendered text if all its placeholders were replaced
+    fn render_complete(&self, text: &str) -> Result<(String, bool)> {
+        expression::render(text, |key| Ok(self.values.get_value(key).cloned()))
     }
-}
 
-fn render_form_urlencoded(
-    form: &mut BTreeMap<String, String>,
-    key: &str,
-    placeholder: &str,
-    val: &str,
-) {
-    if let Some(v) = form.get_mut(key) {
-        replace_pointer!(v, placeholder, val);
+    /// Renames the keys of the map containing placeholders
+    fn render_keys<V>(&self, map: BTreeMap<String, V>) -> Result<BTreeMap<String, V>> {
+        map.into_iter()
+            .map(|(k, v)| Ok((self.render_text(&k)?, v)))
+            .collect()
+    }
+
+    /// Renders in place each one of the values
+    fn render_values<'v>(&self, values: impl IntoIterator<Item = &'v mut String>) -> Result<()> {
+        for value in values {
+            *value = self.render_text(value)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the content of the raw, binary, JSON and GraphQL bodies
+    fn render_body(&self, body: &mut Body) -> Result<()> {
+        match body {
+            Body::Raw { content, .. } | Body::Binary { path: content, .. } => {
+                *content = self.render_text(content)?;
+            }
+            Body::Json(value) => json::render(value, &|text| self.render_complete(text))?,
+            Body::GraphQL(graphql) => {
+                graphql.query = self.render_text(&graphql.query)?;
+                if let Some(variables) = &mut graphql.variables {
+                    json::render(variables, &|text| self.render_complete(text))?;
+                }
+                if let Some(operation_name) = &mut graphql.operation_name {
+                    *operation_name = self.render_text(operation_name)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Renders all the values of the given header
+    fn render_headers(&self, header_map: &mut HeaderMap, header: &HeaderName) -> Result<()> {
+        if let Entry::Occupied(mut entry) = header_map.entry(header) {
+            for header_value in entry.iter_mut() {
+                let value = header_value
+                    .to_str()
+                    .map_err(|_| miette!("failed to convert value of header {header} to string"))?;
+                *header_value = self
+                    .render_text(value)?
+                    .parse()
+                    .map_err(|_| miette!("rendered value of header {header} is invalid"))?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -150,6 +207,8 @@ fn render_form_urlencoded(
 mod test {
     use std::error::Error;
 
+    use rede_schema::Auth;
+
     use super::*;
 
     #[test]
@@ -162,6 +221,10 @@ mod test {
         Content-Type = "application/json"
         Authorization = "Bearer {{token}}"
 
+        [auth.basic]
+        username = "{{user}}"
+        password = "{{password}}"
+
         [query_params]
         page = "{{page}}"
         size = "{{size}}"
@@ -186,6 +249,7 @@ mod test {
             ("page", "1".to_string()),
             ("size", "10".to_string()),
             ("last_name", "renderer".to_string()),
+            ("user", "admin".to_string()),
         ]
         .into_iter()
         .map(|(k, v)| (k, Some(v)))
@@ -215,6 +279,13 @@ mod test {
                 .1,
             "10"
         );
+        assert_eq!(
+            request.auth,
+            Auth::Basic {
+                username: "admin".to_string(),
+                password: "{{password}}".to_string()
+            }
+        );
         if let Body::Raw { content, .. } = request.body {
             assert!(content.contains(r#""id": 1"#));
             assert!(content.contains(r#""name": "test renderer""#));
@@ -226,34 +297,204 @@ mod test {
     }
 
     #[test]
-    fn render_form_data() {
-        let mut form = BTreeMap::new();
-        form.insert(
-            "name".to_string(),
-            FormDataValue::Text("{{name}}".to_string()),
+    fn render_keys() -> std::result::Result<(), Box<dyn Error>> {
+        let request = r#"
+        [http]
+        url = "https://example.com"
+
+        [headers]
+        "{{tenant}}-Id" = "{{id}}"
+        Accept = ["{{mime}}", "text/plain"]
+
+        [query_params]
+        "{{filter}}" = ["{{id}}", "2"]
+
+        [body.x-www-form-urlencoded]
+        "{{tenant}}_name" = "{{name}}"
+        "#;
+
+        let request = rede_parser::parse_request(request).unwrap();
+        let placeholders = (&request).into();
+
+        let values = vec![
+            ("tenant", Some("Acme".to_string())),
+            ("id", Some("1".to_string())),
+            ("mime", Some("application/json".to_string())),
+            ("filter", Some("user".to_string())),
+            ("name", None),
+        ]
+        .into_iter()
+        .collect();
+        let values = PlaceholderValues { values };
+
+        let request = Renderer::new(&placeholders, values).render(request)?;
+
+        assert!(request.templated_headers.is_empty());
+        assert_eq!(request.headers["Acme-Id"].to_str()?, "1");
+        let accept = request
+            .headers
+            .get_all("Accept")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(accept, vec!["application/json", "text/plain"]);
+        assert_eq!(
+            request.query_params,
+            vec![
+                ("user".to_string(), "1".to_string()),
+                ("user".to_string(), "2".to_string())
+            ]
+        );
+        let Body::XFormUrlEncoded(form) = request.body else {
+            panic!("body is not a form")
+        };
+        assert_eq!(form["Acme_name"], "{{name}}");
+        Ok(())
+    }
+
+    #[test]
+    fn render_unresolved_header_name() {
+        let request = r#"
+        http.url = "https://example.com"
+        headers."{{tenant}}-Id" = "1"
+        "#;
+        let request = rede_parser::parse_request(request).unwrap();
+        let placeholders = (&request).into();
+        let values = PlaceholderValues {
+            values: vec![("tenant", None)].into_iter().collect(),
+        };
+        assert!(Renderer::new(&placeholders, values)
+            .render(request)
+            .is_err());
+    }
+
+    #[test]
+    fn render_forms() -> std::result::Result<(), Box<dyn Error>> {
+        let request = r#"
+        http.url = "https://example.com"
+        body.form_data = { name.text = "{{name}}", file = { file = "{{path}}/file", filename = "{{name}}.txt" } }
+        "#;
+        let request = rede_parser::parse_request(request).unwrap();
+        let placeholders = (&request).into();
+        let values = PlaceholderValues {
+            values: vec![
+                ("name", Some("temp_file".to_string())),
+                ("path", Some("/tmp".to_string())),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let request = Renderer::new(&placeholders, values).render(request)?;
+        let Body::FormData(form) = request.body else {
+            panic!("body is not a form")
+        };
+        assert_eq!(
+            form["name"].value,
+            FormDataValue::Text("temp_file".to_string())
         );
-        form.insert(
-            "file".to_string(),
-            FormDataValue::File("{{path}}/file".to_string()),
+        assert_eq!(
+            form["file"].value,
+            FormDataValue::File("/tmp/file".to_string())
         );
+        assert_eq!(form["file"].filename.as_deref(), Some("temp_file.txt"));
+        Ok(())
+    }
 
-        super::render_form_data(&mut form, "name", "{{name}}", "temp_file");
-        super::render_form_data(&mut form, "file", "{{path}}", "/tmp");
+    #[test]
+    fn render_json() -> std::result::Result<(), Box<dyn Error>> {
+        let request = r#"
+        http.url = "https://example.com"
+        body.json = { name = "{{name}}", count = "{{count:int}}", "{{key}}" = "{{missing:bool}}" }
+        "#;
+        let request = rede_parser::parse_request(request).unwrap();
+        let placeholders = (&request).into();
+        let values = PlaceholderValues {
+            values: vec![
+                ("name", Some(r#"x"y"#.to_string())),
+                ("count", Some("3".to_string())),
+                ("key", Some("enabled".to_string())),
+                ("missing", None),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let request = Renderer::new(&placeholders, values).render(request)?;
+        let Body::Json(value) = request.body else {
+            panic!("body is not a JSON")
+        };
+        assert_eq!(
+            value.to_string(),
+            r#"{"count":3,"enabled":"{{missing:bool}}","name":"x\"y"}"#
+        );
 
-        assert_eq!(form["name"], FormDataValue::Text("temp_file".to_string()));
-        assert_eq!(form["file"], FormDataValue::File("/tmp/file".to_string()));
+        let request = rede_parser::parse_request(
+            r#"
+            http.url = "https://example.com"
+            body.graphql = { query = "{ pets }", variables = { limit = "{{limit:int}}" } }
+            "#,
+        )?;
+        let placeholders = (&request).into();
+        let values = PlaceholderValues {
+            values: vec![("limit", Some("abc".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let err = Renderer::new(&placeholders, values)
+            .render(request)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The value of the placeholder `{{limit:int}}` is not valid for its type"
+        );
+        Ok(())
     }
 
     #[test]
-    fn render_form_urlencoded() {
-        let mut form = BTreeMap::new();
-        form.insert("page".to_string(), "{{page}}".to_string());
-        form.insert("order".to_string(), "{{field}}:asc".to_string());
+    fn render_filters() -> std::result::Result<(), Box<dyn Error>> {
+        let request = r#"
+        http.url = "http://{{host | default: 'localhost:8080'}}/{{name | lower}}?q={{query | urlencode}}"
+        headers.Authorization = "Basic {{credentials | base64}}"
+        headers.X-Name = "{{name | upper}}"
+        cookies.user = "{{name | lower}}"
+        "#;
+        let request = rede_parser::parse_request(request).unwrap();
+        let placeholders = (&request).into();
+        let values = PlaceholderValues {
+            values: vec![
+                ("host", None),
+                ("name", Some("Rede".to_string())),
+                ("query", Some("a&b c".to_string())),
+                ("credentials", Some("user:pass".to_string())),
+            ]
+            .into_iter()
+            .collect(),
+        };
 
-        super::render_form_urlencoded(&mut form, "page", "{{page}}", "10");
-        super::render_form_urlencoded(&mut form, "order", "{{field}}", "id");
+        let request = Renderer::new(&placeholders, values).render(request)?;
+        assert_eq!(request.url, "http://localhost:8080/rede?q=a%26b%20c");
+        assert_eq!(request.headers["Authorization"], "Basic dXNlcjpwYXNz");
+        assert_eq!(request.headers["X-Name"], "REDE");
+        assert_eq!(request.cookies["user"], "rede");
+        Ok(())
+    }
 
-        assert_eq!(form["page"], "10".to_string());
-        assert_eq!(form["order"], "id:asc".to_string());
+    #[test]
+    fn render_invalid_filter() {
+        let request = rede_parser::parse_request(r#"http.url = "{{host | uper}}""#).unwrap();
+        let placeholders = (&request).into();
+        let values = PlaceholderValues {
+            values: vec![("host", Some("localhost".to_string()))]
+                .into_iter()
+                .collect(),
+        };
+        let err = Renderer::new(&placeholders, values)
+            .render(request)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The placeholder filter `uper` is not valid"
+        );
     }
 }
@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use miette::{miette, Result};
+
+use crate::expression::render;
 use crate::{Placeholders, ValuePicker};
 
 /// Uses a list of [`ValuePicker`] to resolve the value of [`Placeholders`].
@@ -26,6 +29,16 @@ impl<'req> Resolver<'req> {
     /// The method to use a [`Resolver`]. It takes the placeholders and iterates over them
     /// using its value pickers to find values for each one.
     ///
+    /// The values found can contain placeholders too, like a variable `url = "{{host}}/api"`.
+    /// Those nested placeholders are resolved with the same pickers and replaced in the value,
+    /// repeating the process until the value has no placeholders left. If any of the nested
+    /// placeholders can't be resolved, the placeholder containing it ends unresolved too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the cycle when a placeholder value ends up containing itself,
+    /// like `a -> b -> a`, or the error of any picker failing to pick a value.
+    ///
     /// # Example
     ///
     /// The resolver only requires to be instantiated with the value pickers to use in the
@@ -46,24 +59,70 @@ impl<'req> Resolver<'req> {
     ///     .add_picker(Box::new(EnvVarPicker))
     ///     .add_picker(Box::new(VariablesPicker::new(&request.variables)));
     ///
-    /// let ph_values = resolver.resolve(&placeholders);
+    /// let ph_values = resolver.resolve(&placeholders).unwrap();
     /// assert_eq!(ph_values.get_value("name"), Some(&"variable".to_string()));
     /// assert_eq!(ph_values.get_value("unresolved"), None);
     ///
     /// std::env::set_var("name", "env_var");
     /// std::env::set_var("unresolved", "fixed");
-    /// let ph_values = resolver.resolve(&placeholders);
+    /// let ph_values = resolver.resolve(&placeholders).unwrap();
     /// assert_ne!(ph_values.get_value("name"), Some(&"variable".to_string()));
     /// assert_eq!(ph_values.get_value("name"), Some(&"env_var".to_string()));
     /// assert_eq!(ph_values.get_value("unresolved"), Some(&"fixed".to_string()));
     /// ```
-    #[must_use]
-    pub fn resolve<'ph>(&self, placeholders: &'ph Placeholders) -> PlaceholderValues<'ph> {
-        let values: HashMap<&str, Option<String>> = placeholders
-            .keys()
-            .map(|k| (k, self.pickers.iter().find_map(|p| p.pick_for(k))))
-            .collect();
-        PlaceholderValues { values }
+    pub fn resolve<'ph>(&self, placeholders: &'ph Placeholders) -> Result<PlaceholderValues<'ph>> {
+        let mut found = HashMap::new();
+        let mut values = HashMap::new();
+        for key in placeholders.keys() {
+            let value = self.resolve_key(key, &mut Vec::new(), &mut found)?;
+            values.insert(key, value);
+        }
+        Ok(PlaceholderValues { values })
+    }
+
+    /// Picks the value of a placeholder and replaces in it any nested placeholder. The `chain`
+    /// contains the placeholders being resolved to reach this one, to detect cycles, while
+    /// `found` stores the final values to avoid picking the same placeholder twice.
+    fn resolve_key(
+        &self,
+        key: &str,
+        chain: &mut Vec<String>,
+        found: &mut HashMap<String, Option<String>>,
+    ) -> Result<Option<String>> {
+        if let Some(start) = chain.iter().position(|k| k == key) {
+            let cycle = chain[start..]
+                .iter()
+                .map(String::as_str)
+                .chain([key])
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(miette!(
+                code = "placeholders cycle",
+                url = "https://rede.sotoestevez.dev/reference_guide/request_dsl/placeholders.html",
+                help = "change the values of the placeholders so none of them contains itself",
+                "The placeholders reference each other in a cycle: {cycle}"
+            ));
+        }
+        if let Some(value) = found.get(key) {
+            return Ok(value.clone());
+        }
+
+        let mut value = None;
+        for picker in &self.pickers {
+            value = picker.try_pick_for(key)?;
+            if value.is_some() {
+                break;
+            }
+        }
+        if let Some(picked) = value.take() {
+            chain.push(key.to_string());
+            let (rendered, complete) =
+                render(&picked, |nested| self.resolve_key(nested, chain, found))?;
+            chain.pop();
+            value = complete.then_some(rendered);
+        }
+        found.insert(key.to_string(), value.clone());
+        Ok(value)
     }
 }
 
@@ -75,7 +134,7 @@ pub struct PlaceholderValues<'ph> {
     pub values: HashMap<&'ph str, Option<String>>,
 }
 
-impl<'ph> PlaceholderValues<'ph> {
+impl PlaceholderValues<'_> {
     /// Returns the value for the given key if any. This method doesn't differentiate into keys
     /// of inexistent placeholders and unresolved keys derived from a value not found.
     ///
@@ -126,9 +185,78 @@ impl<'ph> PlaceholderValues<'ph> {
 
 #[cfg(test)]
 mod test {
-    use std::collections::HashMap;
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::{PlaceholderValues, Resolver};
+    use crate::value_picker::{EnvironmentPicker, VariablesPicker};
+    use crate::Placeholders;
+
+    fn placeholders(url: &str) -> Placeholders {
+        let request = rede_schema::Request {
+            url: url.to_string(),
+            ..Default::default()
+        };
+        (&request).into()
+    }
+
+    fn variables(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn resolve_nested() {
+        let placeholders = placeholders("{{url}}/{{id}}");
+        let vars = variables(&[
+            ("url", "{{host}}/{{version}}"),
+            ("version", "v{{major}}"),
+            ("id", "{{major}}"),
+        ]);
+        let env = variables(&[("host", "http://localhost:8080"), ("major", "2")]);
+        let resolver = Resolver::new()
+            .add_picker(Box::new(EnvironmentPicker::new(env)))
+            .add_picker(Box::new(VariablesPicker::new(&vars)));
+
+        let values = resolver.resolve(&placeholders).unwrap();
+        assert_eq!(
+            values.get_value("url"),
+            Some(&"http://localhost:8080/v2".to_string())
+        );
+        assert_eq!(values.get_value("id"), Some(&"2".to_string()));
+        assert_eq!(values.values.len(), 2);
+    }
 
-    use super::PlaceholderValues;
+    #[test]
+    fn resolve_nested_unresolved() {
+        let placeholders = placeholders("{{url}}/{{id}}");
+        let vars = variables(&[("url", "{{host}}/api"), ("id", "1")]);
+        let resolver = Resolver::new().add_picker(Box::new(VariablesPicker::new(&vars)));
+
+        let values = resolver.resolve(&placeholders).unwrap();
+        assert_eq!(values.get_value("url"), None);
+        assert_eq!(values.get_value("id"), Some(&"1".to_string()));
+        assert_eq!(values.unresolved().collect::<Vec<_>>(), vec!["url"]);
+    }
+
+    #[test]
+    fn resolve_cycle() {
+        let placeholders = placeholders("{{url}}");
+        let vars = variables(&[("url", "{{a}}"), ("a", "x{{b}}"), ("b", "{{a}}")]);
+        let resolver = Resolver::new().add_picker(Box::new(VariablesPicker::new(&vars)));
+
+        let err = resolver.resolve(&placeholders).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The placeholders reference each other in a cycle: a -> b -> a"
+        );
+
+        let vars = variables(&[("url", "{{url}}/api")]);
+        let resolver = Resolver::new().add_picker(Box::new(VariablesPicker::new(&vars)));
+        let err = resolver.resolve(&placeholders).unwrap_err();
+        assert!(err.to_string().ends_with("url -> url"));
+    }
 
     #[test]
     fn get_value() {
@@ -5,10 +5,25 @@
 
 use std::{collections::BTreeMap, env};
 
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{SecondsFormat, Utc};
+use miette::{miette, Result};
+
 /// Selects a value to resolve the placeholder.
 pub trait ValuePicker {
     /// For a given placeholder generates a possible value to replace in the request
     fn pick_for(&self, placeholder: &str) -> Option<String>;
+
+    /// Like [`ValuePicker::pick_for`], but failing if the placeholder is meant for this picker
+    /// and it's invalid. By default, it never fails. The [`Resolver`](crate::Resolver) uses
+    /// this method, so the errors are reported instead of leaving the placeholder unresolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the placeholder is invalid for the picker
+    fn try_pick_for(&self, placeholder: &str) -> Result<Option<String>> {
+        Ok(self.pick_for(placeholder))
+    }
 }
 
 /// Picks a value from the request variables.
@@ -45,12 +60,48 @@ impl<'var> VariablesPicker<'var> {
     }
 }
 
-impl<'r> ValuePicker for VariablesPicker<'r> {
+impl ValuePicker for VariablesPicker<'_> {
     fn pick_for(&self, placeholder: &str) -> Option<String> {
         self.vars.get(placeholder).map(String::to_owned)
     }
 }
 
+/// Picks a value from a rede environment, a named set of values loaded on demand to run the same
+/// request against different targets, like `local` or `staging`. Unlike the [`VariablesPicker`]
+/// this picker owns the values, as they don't belong to the request.
+///
+/// # Example
+///
+/// ```
+/// # use std::collections::BTreeMap;
+/// # use crate::rede_placeholders::{ValuePicker, value_picker::EnvironmentPicker};
+/// #
+/// let toml = r#"
+/// host = "https://staging.example.org"
+/// "#;
+/// let environment = rede_parser::parse_environment(toml).unwrap();
+/// let picker = EnvironmentPicker::new(environment);
+/// assert_eq!(picker.pick_for("host"), Some("https://staging.example.org".to_string()));
+/// assert_eq!(picker.pick_for("missing"), None);
+/// ```
+pub struct EnvironmentPicker {
+    values: BTreeMap<String, String>,
+}
+
+impl EnvironmentPicker {
+    /// Generates a picker holding the values of the environment.
+    #[must_use]
+    pub fn new(values: BTreeMap<String, String>) -> Self {
+        Self { values }
+    }
+}
+
+impl ValuePicker for EnvironmentPicker {
+    fn pick_for(&self, placeholder: &str) -> Option<String> {
+        self.values.get(placeholder).map(String::to_owned)
+    }
+}
+
 /// Picks a value from the environment variables
 ///
 /// # Example
@@ -70,3 +121,133 @@ impl ValuePicker for EnvVarPicker {
         env::var(placeholder).ok()
     }
 }
+
+/// Generates values for the dynamic placeholders, those starting with `$` like `{{$uuid}}`.
+/// Some of them accept arguments separated by spaces, like `{{$randomInt 1 100}}`.
+///
+/// - `$uuid`: random UUID v4.
+/// - `$timestamp`: current Unix timestamp in seconds.
+/// - `$isoDate`: current UTC date and time in ISO 8601 format.
+/// - `$randomInt [min max]`: random integer in the inclusive range, `0 1000` by default.
+/// - `$base64 text`: the given text encoded in base64.
+///
+/// The unknown functions are left unresolved, while the known ones with invalid arguments, like
+/// `{{$randomInt 100 1}}`, fail with [`ValuePicker::try_pick_for`].
+///
+/// # Example
+///
+/// ```
+/// # use crate::rede_placeholders::{ValuePicker, value_picker::DynamicPicker};
+/// #
+/// assert_eq!(DynamicPicker.pick_for("$uuid").map(|uuid| uuid.len()), Some(36));
+/// assert_eq!(DynamicPicker.pick_for("$randomInt 7 7"), Some("7".to_string()));
+/// assert_eq!(DynamicPicker.pick_for("$base64 user:pass"), Some("dXNlcjpwYXNz".to_string()));
+/// assert_eq!(DynamicPicker.pick_for("$unknown"), None);
+/// assert_eq!(DynamicPicker.pick_for("uuid"), None);
+/// assert!(DynamicPicker.try_pick_for("$randomInt 100 1").is_err());
+/// ```
+pub struct DynamicPicker;
+
+impl ValuePicker for DynamicPicker {
+    fn pick_for(&self, placeholder: &str) -> Option<String> {
+        self.try_pick_for(placeholder).ok().flatten()
+    }
+
+    fn try_pick_for(&self, placeholder: &str) -> Result<Option<String>> {
+        let Some(function) = placeholder.strip_prefix('$') else {
+            return Ok(None);
+        };
+        let (name, args) = function.split_once(' ').unwrap_or((function, ""));
+        let value = match name {
+            "uuid" => uuid::Uuid::new_v4().to_string(),
+            "timestamp" => Utc::now().timestamp().to_string(),
+            "isoDate" => Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            "randomInt" => random_int(placeholder, args)?,
+            "base64" => STANDARD.encode(args),
+            _ => return Ok(None),
+        };
+        Ok(Some(value))
+    }
+}
+
+fn random_int(placeholder: &str, args: &str) -> Result<String> {
+    let args = args
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<i64>, _>>();
+    let (min, max) = match args.as_deref() {
+        Ok([]) => (0, 1000),
+        Ok(&[min, max]) if min <= max => (min, max),
+        _ => {
+            return Err(miette!(
+                code = "invalid dynamic placeholder",
+                url = "https://rede.sotoestevez.dev/reference_guide/request_dsl/placeholders.html#dynamic-placeholders",
+                help = "`$randomInt` expects no arguments or two integers, `min max`, with `min` not greater than `max`",
+                "The dynamic placeholder `{placeholder}` has invalid arguments"
+            ))
+        }
+    };
+    Ok(fastrand::i64(min..=max).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DynamicPicker, ValuePicker};
+
+    #[test]
+    fn dynamic_picker() {
+        let uuid = DynamicPicker.pick_for("$uuid").unwrap();
+        assert_eq!(uuid.len(), 36);
+        assert_ne!(DynamicPicker.pick_for("$uuid").unwrap(), uuid);
+
+        let timestamp: i64 = DynamicPicker
+            .pick_for("$timestamp")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(timestamp > 1_700_000_000);
+
+        let date = DynamicPicker.pick_for("$isoDate").unwrap();
+        assert!(chrono::DateTime::parse_from_rfc3339(&date).is_ok());
+        assert!(date.ends_with('Z'));
+
+        let int: i64 = DynamicPicker
+            .pick_for("$randomInt 1 3")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=3).contains(&int));
+        let int: i64 = DynamicPicker
+            .pick_for("$randomInt")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((0..=1000).contains(&int));
+        assert_eq!(DynamicPicker.pick_for("$randomInt -5 -5").unwrap(), "-5");
+        assert_eq!(DynamicPicker.pick_for("$randomInt 10 1"), None);
+        assert_eq!(DynamicPicker.pick_for("$randomInt 1"), None);
+        assert_eq!(DynamicPicker.pick_for("$randomInt a b"), None);
+        for invalid in ["$randomInt 10 1", "$randomInt 1", "$randomInt a b"] {
+            let err = DynamicPicker.try_pick_for(invalid).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("The dynamic placeholder `{invalid}` has invalid arguments")
+            );
+            assert!(err
+                .help()
+                .unwrap()
+                .to_string()
+                .contains("`$randomInt` expects"));
+        }
+        assert_eq!(DynamicPicker.try_pick_for("$unknown").unwrap(), None);
+        assert!(DynamicPicker.try_pick_for("$uuid").unwrap().is_some());
+
+        assert_eq!(
+            DynamicPicker.pick_for("$base64 hello world"),
+            Some("aGVsbG8gd29ybGQ=".to_string())
+        );
+        assert_eq!(DynamicPicker.pick_for("$base64"), Some(String::new()));
+        assert_eq!(DynamicPicker.pick_for("$unknown"), None);
+        assert_eq!(DynamicPicker.pick_for("timestamp"), None);
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use http::StatusCode;
+
+/// Assertions to check against the response of the request
+#[derive(Debug, Default, PartialEq)]
+pub struct Asserts {
+    /// Expected status code of the response
+    pub status: Option<StatusCode>,
+    /// Expected values of the response headers
+    pub headers: BTreeMap<String, HeaderAssert>,
+    /// Expected values of the JSON response body, keyed by the JSON path of each value
+    pub body: BTreeMap<String, String>,
+    /// Maximum time that the response can take to arrive
+    pub max_response_time: Option<Duration>,
+}
+
+/// Types of checks that can be performed over a header value
+#[derive(Debug, PartialEq)]
+pub enum HeaderAssert {
+    /// The header value must be exactly the given one
+    Equals(String),
+    /// The header value must contain the given one
+    Contains(String),
+}
+
+impl Asserts {
+    /// Returns the number of assertions defined
+    ///
+    /// ```
+    /// # use rede_schema::Asserts;
+    /// let asserts = Asserts {
+    ///     status: Some(http::StatusCode::OK),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(asserts.len(), 1);
+    /// ```
+    #[must_use]
+    pub fn len(&self) -> usize {
+        usize::from(self.status.is_some())
+            + self.headers.len()
+            + self.body.len()
+            + usize::from(self.max_response_time.is_some())
+    }
+
+    /// Returns true if there's no assertion defined
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl HeaderAssert {
+    /// Checks if the given header value satisfies the assertion
+    ///
+    /// ```
+    /// # use rede_schema::asserts::HeaderAssert;
+    /// assert!(HeaderAssert::Contains("json".to_string()).is_satisfied_by("application/json"));
+    /// assert!(!HeaderAssert::Equals("json".to_string()).is_satisfied_by("application/json"));
+    /// ```
+    #[must_use]
+    pub fn is_satisfied_by(&self, value: &str) -> bool {
+        match self {
+            HeaderAssert::Equals(expected) => value == expected,
+            HeaderAssert::Contains(expected) => value.contains(expected.as_str()),
+        }
+    }
+}
+
+impl Display for HeaderAssert {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeaderAssert::Equals(value) => write!(f, "equal to \"{value}\""),
+            HeaderAssert::Contains(value) => write!(f, "containing \"{value}\""),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt::{Display, Formatter};
+
+/// Authentication method to apply to the request
+#[derive(Debug, Default, PartialEq)]
+pub enum Auth {
+    /// No authentication
+    #[default]
+    None,
+    /// Basic authentication, sending the encoded credentials in the `Authorization` header
+    Basic { username: String, password: String },
+    /// Bearer authentication, sending the token in the `Authorization` header
+    Bearer { token: String },
+    /// Digest authentication, answering the challenge of the server with the hashed credentials
+    Digest { username: String, password: String },
+    /// API key sent in a header or a query param
+    ApiKey {
+        key: String,
+        value: String,
+        location: ApiKeyLocation,
+    },
+    /// `OAuth2` access token obtained from the token endpoint and sent as bearer token
+    OAuth2(OAuth2),
+}
+
+/// Configuration of the `OAuth2` grant used to obtain the access token
+#[derive(Debug, PartialEq)]
+pub struct OAuth2 {
+    /// URL of the token endpoint of the authorization server
+    pub token_url: String,
+    /// Identifier of the client
+    pub client_id: String,
+    /// Secret of the client, it can be empty for public clients
+    pub client_secret: String,
+    /// Scope to request, it can be empty to use the default one of the server
+    pub scope: String,
+    /// Way to send the client credentials to the token endpoint
+    pub client_auth: ClientAuth,
+    /// Grant to perform to obtain the token
+    pub grant: Grant,
+}
+
+/// Authentication of the `OAuth2` client against the token endpoint, as defined in RFC 6749
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ClientAuth {
+    /// The client id and secret are sent with HTTP Basic authentication
+    Basic,
+    /// The client id and secret are sent in the body of the token request
+    #[default]
+    Post,
+}
+
+/// `OAuth2` grant types supported to obtain the access token
+#[derive(Debug, PartialEq)]
+pub enum Grant {
+    /// Client credentials grant, using only the client authentication
+    ClientCredentials,
+    /// Resource owner password credentials grant
+    Password { username: String, password: String },
+    /// Refresh token grant, exchanging the given refresh token for a new access token
+    RefreshToken { refresh_token: String },
+}
+
+/// Part of the request where the API key is sent
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ApiKeyLocation {
+    /// The key is the name of a header
+    #[default]
+    Header,
+    /// The key is the name of a query param
+    Query,
+}
+
+impl Auth {
+    /// Returns true if the request has no authentication
+    #[must_use]
+    pub fn is_none(&self) -> bool {
+        matches!(self, Auth::None)
+    }
+
+    /// Returns the text values of the authentication, like the credentials or tokens
+    #[must_use]
+    pub fn values(&self) -> Vec<&String> {
+        match self {
+            Auth::None => vec![],
+            Auth::Basic { username, password } | Auth::Digest { username, password } => {
+                vec![username, password]
+            }
+            Auth::Bearer { token } => vec![token],
+            Auth::ApiKey { key, value, .. } => vec![key, value],
+            Auth::OAuth2(oauth2) => {
+                let mut values = vec![
+                    &oauth2.token_url,
+                    &oauth2.client_id,
+                    &oauth2.client_secret,
+                    &oauth2.scope,
+                ];
+                match &oauth2.grant {
+                    Grant::ClientCredentials => {}
+                    Grant::Password { username, password } => values.extend([username, password]),
+                    Grant::RefreshToken { refresh_token } => values.push(refresh_token),
+                }
+                values
+            }
+        }
+    }
+
+    /// Returns mutable references to the text values of the authentication, to allow their
+    /// transformation, like the rendering of placeholders.
+    pub fn values_mut(&mut self) -> Vec<&mut String> {
+        match self {
+            Auth::None => vec![],
+            Auth::Basic { username, password } | Auth::Digest { username, password } => {
+                vec![username, password]
+            }
+            Auth::Bearer { token } => vec![token],
+            Auth::ApiKey { key, value, .. } => vec![key, value],
+            Auth::OAuth2(oauth2) => {
+                let mut values = vec![
+                    &mut oauth2.token_url,
+                    &mut oauth2.client_id,
+                    &mut oauth2.client_secret,
+                    &mut oauth2.scope,
+                ];
+                match &mut oauth2.grant {
+                    Grant::ClientCredentials => {}
+                    Grant::Password { username, password } => values.extend([username, password]),
+                    Grant::RefreshToken { refresh_token } => values.push(refresh_token),
+                }
+                values
+            }
+        }
+    }
+}
+
+impl Display for Auth {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Auth::None => Ok(()),
+            Auth::Basic { username, .. } => write!(f, "basic {username}"),
+            Auth::Bearer { .. } => f.write_str("bearer"),
+            Auth::Digest { username, .. } => write!(f, "digest {username}"),
+            Auth::ApiKey { key, location, .. } => write!(f, "api key {key} ({location})"),
+            Auth::OAuth2(oauth2) => write!(f, "oauth2 {} ({})", oauth2.client_id, oauth2.grant),
+        }
+    }
+}
+
+impl Display for ApiKeyLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiKeyLocation::Header => f.write_str("header"),
+            ApiKeyLocation::Query => f.write_str("query"),
+        }
+    }
+}
+
+impl Display for Grant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Grant::ClientCredentials => f.write_str("client_credentials"),
+            Grant::Password { .. } => f.write_str("password"),
+            Grant::RefreshToken { .. } => f.write_str("refresh_token"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values_mut() {
+        let mut auth = Auth::Basic {
+            username: "user".to_string(),
+            password: "{{password}}".to_string(),
+        };
+        for value in auth.values_mut() {
+            *value = value.replace("{{password}}", "secret");
+        }
+        assert_eq!(
+            auth,
+            Auth::Basic {
+                username: "user".to_string(),
+                password: "secret".to_string()
+            }
+        );
+        assert!(Auth::None.values_mut().is_empty());
+    }
+
+    #[test]
+    fn oauth2_values() {
+        let auth = Auth::OAuth2(OAuth2 {
+            token_url: "{{host}}/token".to_string(),
+            client_id: "rede".to_string(),
+            client_secret: "{{secret}}".to_string(),
+            scope: String::new(),
+            client_auth: ClientAuth::Basic,
+            grant: Grant::Password {
+                username: "user".to_string(),
+                password: "{{password}}".to_string(),
+            },
+        });
+        assert_eq!(auth.values().len(), 6);
+        assert_eq!(auth.to_string(), "oauth2 rede (password)");
+    }
+}
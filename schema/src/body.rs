@@ -1,6 +1,11 @@
+use http::HeaderMap;
 use mime::Mime;
+use serde_json::Value;
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
 
 /// Body of the request, it contains all the currently supported options
 #[derive(Debug, Default, PartialEq)]
@@ -11,13 +16,117 @@ pub enum Body {
     /// The body of the request is in text format. This body can be bundled with a Content-Type
     /// like application/json to send JSONs with full meaning.
     Raw { content: String, mime: Mime },
+    /// The body is a JSON document, sent with the application/json Content-Type. Unlike a raw
+    /// body, its placeholders are rendered into the strings of the document, so their values
+    /// are escaped and typed placeholders like `{{count:int}}` can insert other types.
+    Json(Value),
     /// The body of the request contains a file located at the given path.
     /// This body can be bundled with Content-Type headers like application/pdf.
     Binary { path: String, mime: Mime },
     /// The body is an HTTP form.
-    FormData(BTreeMap<String, FormDataValue>),
+    FormData(BTreeMap<String, FormDataPart>),
     /// The body of the request is an HTTP form encoded in the URL.
     XFormUrlEncoded(BTreeMap<String, String>),
+    /// The body is a GraphQL operation, sent as a JSON with the query and its variables.
+    GraphQL(GraphQL),
+}
+
+/// GraphQL operation of the body
+#[derive(Debug, Default, PartialEq)]
+pub struct GraphQL {
+    pub query: String,
+    /// JSON object with the values of the variables of the query
+    pub variables: Option<Value>,
+    pub operation_name: Option<String>,
+}
+
+impl GraphQL {
+    /// Returns the JSON to send with the operation, following the GraphQL over HTTP format
+    ///
+    /// ```
+    /// # use rede_schema::body::GraphQL;
+    /// let graphql = GraphQL {
+    ///     query: "query Pet($id: ID!) { pet(id: $id) { name } }".to_string(),
+    ///     variables: Some(serde_json::json!({ "id": 1 })),
+    ///     operation_name: Some("Pet".to_string()),
+    /// };
+    /// assert_eq!(
+    ///     graphql.to_json(),
+    ///     r#"{"query":"query Pet($id: ID!) { pet(id: $id) { name } }","operationName":"Pet","variables":{"id":1}}"#
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut json = vec![format!(r#""query":{}"#, Value::from(self.query.as_str()))];
+        if let Some(operation_name) = &self.operation_name {
+            let operation_name = Value::from(operation_name.as_str());
+            json.push(format!(r#""operationName":{operation_name}"#));
+        }
+        if let Some(variables) = &self.variables {
+            json.push(format!(r#""variables":{variables}"#));
+        }
+        format!("{{{}}}", json.join(","))
+    }
+}
+
+/// Part of a form data body, with its value and the optional metadata of the part
+#[derive(Debug, Default, PartialEq)]
+pub struct FormDataPart {
+    pub value: FormDataValue,
+    /// Name of the file sent in the part, by default the name of the file of the path
+    pub filename: Option<String>,
+    /// MIME of the part, if not set the files will guess it from their extension
+    pub content_type: Option<Mime>,
+    pub headers: HeaderMap,
+}
+
+impl FormDataPart {
+    /// Returns the name of the file to send with the part: the one specified or, for files,
+    /// the name of the file in the path
+    ///
+    /// ```
+    /// # use rede_schema::body::{FormDataPart, FormDataValue};
+    /// let part = FormDataPart::from(FormDataValue::File("/tmp/avatar.png".to_string()));
+    /// assert_eq!(part.file_name().as_deref(), Some("avatar.png"));
+    /// ```
+    #[must_use]
+    pub fn file_name(&self) -> Option<Cow<'_, str>> {
+        match (&self.filename, &self.value) {
+            (Some(filename), _) => Some(Cow::Borrowed(filename)),
+            (None, FormDataValue::File(path)) => Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy()),
+            (None, FormDataValue::Text(_)) => None,
+        }
+    }
+
+    /// Returns the MIME type of the part: the one specified or, for files, the one guessed from
+    /// the extension of the file, defaulting to `application/octet-stream`
+    ///
+    /// ```
+    /// # use rede_schema::body::{FormDataPart, FormDataValue};
+    /// let part = FormDataPart::from(FormDataValue::File("/tmp/avatar.png".to_string()));
+    /// assert_eq!(part.mime(), Some(mime::IMAGE_PNG));
+    /// ```
+    #[must_use]
+    pub fn mime(&self) -> Option<Mime> {
+        match (&self.content_type, &self.value) {
+            (Some(mime), _) => Some(mime.clone()),
+            (None, FormDataValue::File(path)) => {
+                Some(mime_guess::from_path(path).first_or_octet_stream())
+            }
+            (None, FormDataValue::Text(_)) => None,
+        }
+    }
+}
+
+impl From<FormDataValue> for FormDataPart {
+    fn from(value: FormDataValue) -> Self {
+        Self {
+            value,
+            ..Default::default()
+        }
+    }
 }
 
 /// Types of values for form data, can be text or binaries
@@ -27,6 +136,63 @@ pub enum FormDataValue {
     File(String),
 }
 
+impl Default for FormDataValue {
+    fn default() -> Self {
+        FormDataValue::Text(String::new())
+    }
+}
+
+/// Algorithm to compress the body with before sending it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    Gzip,
+    Deflate,
+    Brotli,
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the token of the algorithm used in the `Content-Encoding` and `Accept-Encoding`
+    /// headers
+    ///
+    /// ```
+    /// # use rede_schema::body::Compression;
+    /// assert_eq!(Compression::Brotli.encoding(), "br");
+    /// ```
+    #[must_use]
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Deflate => "deflate",
+            Compression::Brotli => "br",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    /// Parses the algorithm from its encoding token, `brotli` is also accepted
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "gzip" => Ok(Compression::Gzip),
+            "deflate" => Ok(Compression::Deflate),
+            "br" | "brotli" => Ok(Compression::Brotli),
+            "zstd" => Ok(Compression::Zstd),
+            _ => Err(format!(
+                "unknown compression {s}, expected gzip, deflate, br or zstd"
+            )),
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.encoding())
+    }
+}
+
 impl Body {
     /// Returns the MIME type associated with the body
     ///
@@ -45,16 +211,18 @@ impl Body {
             Body::Raw { mime, .. } | Body::Binary { mime, .. } => Some(mime),
             Body::FormData(_) => Some(&mime::MULTIPART_FORM_DATA),
             Body::XFormUrlEncoded(_) => Some(&mime::APPLICATION_WWW_FORM_URLENCODED),
+            Body::Json(_) | Body::GraphQL(_) => Some(&mime::APPLICATION_JSON),
         }
     }
 }
 
 impl Display for Body {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        use Body::{Binary, FormData, None, Raw, XFormUrlEncoded};
+        use Body::{Binary, FormData, GraphQL, Json, None, Raw, XFormUrlEncoded};
         match self {
             None => Ok(()),
             Raw { content, .. } => write!(f, "{content}"),
+            Json(value) => write!(f, "{value:#}"),
             Binary { path, .. } => write!(f, "@{path}"),
             FormData(map) => {
                 for (k, v) in map {
@@ -68,10 +236,37 @@ impl Display for Body {
                 }
                 Ok(())
             }
+            GraphQL(graphql) => write!(f, "{graphql}"),
         }
     }
 }
 
+impl Display for GraphQL {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.query.trim())?;
+        if let Some(operation_name) = &self.operation_name {
+            write!(f, "\noperation: {operation_name}")?;
+        }
+        if let Some(variables) = &self.variables {
+            write!(f, "\nvariables: {variables}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for FormDataPart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)?;
+        if let Some(filename) = &self.filename {
+            write!(f, "; filename={filename}")?;
+        }
+        if let Some(content_type) = &self.content_type {
+            write!(f, "; type={content_type}")?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for FormDataValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -86,7 +281,7 @@ mod test {
     use super::*;
 
     #[test]
-    #[ignore]
+    #[ignore = "prints the bodies to check their format manually"]
     fn display() {
         let content = r#"
         {
@@ -107,8 +302,19 @@ mod test {
         println!("{body}");
 
         let mut map = BTreeMap::new();
-        map.insert("text".to_string(), FormDataValue::Text("value".to_string()));
-        map.insert("file".to_string(), FormDataValue::File("path".to_string()));
+        map.insert(
+            "text".to_string(),
+            FormDataValue::Text("value".to_string()).into(),
+        );
+        map.insert(
+            "file".to_string(),
+            FormDataPart {
+                value: FormDataValue::File("path".to_string()),
+                filename: Some("file.png".to_string()),
+                content_type: Some(mime::IMAGE_PNG),
+                headers: HeaderMap::new(),
+            },
+        );
         let body = Body::FormData(map);
         println!("{body}");
 
@@ -117,5 +323,49 @@ mod test {
         map.insert("other".to_string(), "val".to_string());
         let body = Body::XFormUrlEncoded(map);
         println!("{body}");
+
+        let body = Body::GraphQL(GraphQL {
+            query: "{ pets { name } }".to_string(),
+            variables: Some(serde_json::json!({ "limit": 10 })),
+            operation_name: None,
+        });
+        println!("{body}");
+    }
+
+    #[test]
+    fn graphql_to_json() {
+        let graphql = GraphQL {
+            query: "{ pets(limit: $limit) { \"name\" } }".to_string(),
+            variables: Some(serde_json::json!({ "limit": 10 })),
+            operation_name: None,
+        };
+        assert_eq!(
+            graphql.to_json(),
+            r#"{"query":"{ pets(limit: $limit) { \"name\" } }","variables":{"limit":10}}"#
+        );
+        assert_eq!(GraphQL::default().to_json(), r#"{"query":""}"#);
+    }
+
+    #[test]
+    fn form_data_part_metadata() {
+        let text = FormDataPart::from(FormDataValue::Text("value".to_string()));
+        assert_eq!(text.file_name(), None);
+        assert_eq!(text.mime(), None);
+
+        let file = FormDataPart {
+            value: FormDataValue::File("/tmp/data".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(file.file_name().as_deref(), Some("data"));
+        assert_eq!(file.mime(), Some(mime::APPLICATION_OCTET_STREAM));
+
+        let file = FormDataPart {
+            value: FormDataValue::File("/tmp/data.json".to_string()),
+            filename: Some("report".to_string()),
+            content_type: Some(mime::TEXT_PLAIN),
+            ..Default::default()
+        };
+        assert_eq!(file.file_name().as_deref(), Some("report"));
+        assert_eq!(file.mime(), Some(mime::TEXT_PLAIN));
     }
 }
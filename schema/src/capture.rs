@@ -0,0 +1,26 @@
+use std::fmt::{Display, Formatter};
+
+/// Part of the response to extract a value from, to be stored and used in later requests
+#[derive(Debug, PartialEq)]
+pub enum Capture {
+    /// Value of the JSON response body located at the given JSON pointer, like `/user/id`
+    Json(String),
+    /// Value of the response header with the given name
+    Header(String),
+    /// Match of the given regular expression over the response body. If the expression has
+    /// groups, the first one is captured instead of the whole match.
+    Regex(String),
+    /// Status code of the response
+    Status,
+}
+
+impl Display for Capture {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Capture::Json(pointer) => write!(f, "json {pointer}"),
+            Capture::Header(name) => write!(f, "header {name}"),
+            Capture::Regex(regex) => write!(f, "regex {regex}"),
+            Capture::Status => f.write_str("status"),
+        }
+    }
+}
@@ -1,6 +1,50 @@
+use std::fmt::{Display, Formatter};
+
 /// Contains the different properties that can be defined for an input parameter.
 #[derive(Debug, Default, PartialEq)]
 pub struct InputParam {
     /// Hint to provide to the user when asking for the input
     pub hint: Option<String>,
+    /// Marks the value as secret, hiding it while it's typed and masking it in the output
+    pub secret: bool,
+    /// Type of the value, the input must be valid for it
+    pub kind: InputType,
+    /// Closed list of values the user can select from
+    pub choices: Vec<String>,
+    /// Value offered to the user when asking for the input
+    pub default: Option<String>,
+}
+
+/// Types of value accepted by an input parameter
+#[derive(Debug, Default, PartialEq)]
+pub enum InputType {
+    /// Any text
+    #[default]
+    String,
+    /// Integer number
+    Int,
+    /// Floating point number
+    Float,
+    /// `true` or `false`
+    Bool,
+    /// Absolute URL
+    Url,
+    /// Email address
+    Email,
+    /// Text matching the contained regular expression
+    Regex(String),
+}
+
+impl Display for InputType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputType::String => f.write_str("string"),
+            InputType::Int => f.write_str("int"),
+            InputType::Float => f.write_str("float"),
+            InputType::Bool => f.write_str("bool"),
+            InputType::Url => f.write_str("url"),
+            InputType::Email => f.write_str("email"),
+            InputType::Regex(pattern) => write!(f, "regex {pattern}"),
+        }
+    }
 }
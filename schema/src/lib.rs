@@ -6,18 +6,34 @@
 
 #![warn(clippy::pedantic)]
 
+/// Contains all the specific types used in the asserts
+pub mod asserts;
+/// Contains all the specific types used in the authentication
+pub mod auth;
 /// Contains all the specific types used in the body
 pub mod body;
+/// Contains all the specific types used in the response captures
+pub mod capture;
+/// Contains all the specific types used in the TLS configuration
+pub mod tls;
 
 #[cfg(feature = "input_params")]
 mod input_param;
 
+#[doc(inline)]
+pub use asserts::Asserts;
+#[doc(inline)]
+pub use auth::Auth;
 #[doc(inline)]
 pub use body::Body;
+#[doc(inline)]
+pub use capture::Capture;
+#[doc(inline)]
+pub use tls::Tls;
 
 #[cfg(feature = "input_params")]
 #[doc(inline)]
-pub use input_param::InputParam;
+pub use input_param::{InputParam, InputType};
 
 use std::collections::BTreeMap;
 
@@ -25,7 +41,7 @@ use http::{HeaderMap, Method, Version};
 
 /// Representation of a rede HTTP request. Contains all the supported content by the current schema
 /// to allow the creation and dispatching of the HTTP request with the command-line interface.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Request {
     /// HTTP method of the request
     pub method: Method,
@@ -37,12 +53,29 @@ pub struct Request {
     pub metadata: BTreeMap<String, String>,
     /// Headers of the request
     pub headers: HeaderMap,
+    /// Headers whose names contain placeholders, they are moved into the
+    /// [`headers`](Self::headers) once rendered
+    pub templated_headers: Vec<(String, String)>,
     /// Query parameters of the request
     pub query_params: Vec<(String, String)>,
+    /// Cookies to send with the request, along with the ones of the cookie jar, if any
+    pub cookies: BTreeMap<String, String>,
     /// Body of the request
     pub body: Body,
+    /// Algorithm to compress the body with before sending it, if any
+    pub compression: Option<body::Compression>,
+    /// Whether to decompress the compressed responses, they are decompressed if not specified
+    pub decompress: Option<bool>,
+    /// Authentication of the request
+    pub auth: Auth,
+    /// TLS configuration of the connection
+    pub tls: Tls,
     /// Variables to provide values for placeholders in the request
     pub variables: BTreeMap<String, String>,
+    /// Assertions to check against the response of the request
+    pub asserts: Asserts,
+    /// Values to extract from the response to use them in later requests
+    pub captures: BTreeMap<String, Capture>,
 
     #[cfg(feature = "input_params")]
     /// Keys of placeholders to ask the user for input
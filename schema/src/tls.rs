@@ -0,0 +1,170 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// TLS configuration of the connection, on top of the defaults of the client
+#[derive(Debug, Default, PartialEq)]
+pub struct Tls {
+    /// Path of a PEM file with the CA certificates to trust along with the ones of the system
+    pub ca_cert: Option<String>,
+    /// Certificate to authenticate the client against the server
+    pub client_cert: Option<ClientCert>,
+    /// Minimum version of TLS accepted for the connection
+    pub min_version: Option<TlsVersion>,
+    /// Accepts invalid certificates and hostnames, it should only be used for testing
+    pub insecure: bool,
+}
+
+/// Certificate of the client, along with its private key
+#[derive(Debug, PartialEq)]
+pub enum ClientCert {
+    /// PEM files of the certificate chain and its PKCS#8 private key
+    Pem { cert: String, key: String },
+    /// PKCS#12 archive with the certificate and its key, protected by the password
+    Pkcs12 { path: String, password: String },
+}
+
+/// Versions of TLS that can be set as minimum
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum TlsVersion {
+    Tls1_0,
+    Tls1_1,
+    Tls1_2,
+    Tls1_3,
+}
+
+impl Tls {
+    /// Returns the configuration with the values missing in this one taken from the other.
+    /// The connection is insecure if any of them is.
+    ///
+    /// ```
+    /// # use rede_schema::tls::{Tls, TlsVersion};
+    /// let cli = Tls { ca_cert: Some("ca.pem".to_string()), ..Default::default() };
+    /// let request = Tls {
+    ///     ca_cert: Some("other.pem".to_string()),
+    ///     min_version: Some(TlsVersion::Tls1_2),
+    ///     ..Default::default()
+    /// };
+    /// let tls = cli.or(request);
+    /// assert_eq!(tls.ca_cert.as_deref(), Some("ca.pem"));
+    /// assert_eq!(tls.min_version, Some(TlsVersion::Tls1_2));
+    /// ```
+    #[must_use]
+    pub fn or(self, other: Tls) -> Tls {
+        Tls {
+            ca_cert: self.ca_cert.or(other.ca_cert),
+            client_cert: self.client_cert.or(other.client_cert),
+            min_version: self.min_version.or(other.min_version),
+            insecure: self.insecure || other.insecure,
+        }
+    }
+
+    /// Returns the text values of the configuration, the paths of the files and the password
+    #[must_use]
+    pub fn values(&self) -> Vec<&String> {
+        let mut values: Vec<&String> = self.ca_cert.iter().collect();
+        match &self.client_cert {
+            Some(ClientCert::Pem { cert, key }) => values.extend([cert, key]),
+            Some(ClientCert::Pkcs12 { path, password }) => values.extend([path, password]),
+            None => {}
+        }
+        values
+    }
+
+    /// Returns the mutable text values of the configuration, the paths of the files and the
+    /// password
+    #[must_use]
+    pub fn values_mut(&mut self) -> Vec<&mut String> {
+        let mut values: Vec<&mut String> = self.ca_cert.iter_mut().collect();
+        match &mut self.client_cert {
+            Some(ClientCert::Pem { cert, key }) => values.extend([cert, key]),
+            Some(ClientCert::Pkcs12 { path, password }) => values.extend([path, password]),
+            None => {}
+        }
+        values
+    }
+}
+
+impl TlsVersion {
+    /// Returns the number of the version
+    ///
+    /// ```
+    /// # use rede_schema::tls::TlsVersion;
+    /// assert_eq!(TlsVersion::Tls1_2.number(), "1.2");
+    /// ```
+    #[must_use]
+    pub fn number(&self) -> &'static str {
+        match self {
+            TlsVersion::Tls1_0 => "1.0",
+            TlsVersion::Tls1_1 => "1.1",
+            TlsVersion::Tls1_2 => "1.2",
+            TlsVersion::Tls1_3 => "1.3",
+        }
+    }
+}
+
+impl FromStr for TlsVersion {
+    type Err = String;
+
+    /// Parses the version from its number, optionally prefixed by `TLS` or `TLSv`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lowercase = s.trim().to_ascii_lowercase();
+        let version = lowercase.strip_prefix("tls").unwrap_or(&lowercase);
+        let version = version.strip_prefix('v').unwrap_or(version).trim_start();
+        match version {
+            "1" | "1.0" => Ok(TlsVersion::Tls1_0),
+            "1.1" => Ok(TlsVersion::Tls1_1),
+            "1.2" => Ok(TlsVersion::Tls1_2),
+            "1.3" => Ok(TlsVersion::Tls1_3),
+            _ => Err(format!(
+                "unknown TLS version {s}, expected 1.0, 1.1, 1.2 or 1.3"
+            )),
+        }
+    }
+}
+
+impl Display for TlsVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TLS {}", self.number())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_version() {
+        assert_eq!("1.2".parse(), Ok(TlsVersion::Tls1_2));
+        assert_eq!("TLSv1.3".parse(), Ok(TlsVersion::Tls1_3));
+        assert_eq!("tls 1.0".parse(), Ok(TlsVersion::Tls1_0));
+        assert!("1.4".parse::<TlsVersion>().is_err());
+        assert_eq!(TlsVersion::Tls1_1.to_string(), "TLS 1.1");
+    }
+
+    #[test]
+    fn values_mut() {
+        let mut tls = Tls {
+            ca_cert: Some("{{dir}}/ca.pem".to_string()),
+            client_cert: Some(ClientCert::Pkcs12 {
+                path: "client.p12".to_string(),
+                password: "{{password}}".to_string(),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(tls.values().len(), 3);
+        for value in tls.values_mut() {
+            *value = value
+                .replace("{{dir}}", "certs")
+                .replace("{{password}}", "secret");
+        }
+        assert_eq!(tls.ca_cert.as_deref(), Some("certs/ca.pem"));
+        assert_eq!(
+            tls.client_cert,
+            Some(ClientCert::Pkcs12 {
+                path: "client.p12".to_string(),
+                password: "secret".to_string()
+            })
+        );
+        assert!(Tls::default().values_mut().is_empty());
+    }
+}